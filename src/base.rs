@@ -1,29 +1,195 @@
 use UnsafeCastable;
 use Constructable;
 use Castable;
+use Cast;
+use virtual_fn::check_virtuals;
 
 use std::any::{Any, TypeId};
 use std::fmt;
+use std::sync::{Arc, Weak};
 
+/// The `TypeId`, name and byte offset of every layer of a correctly constructed object.
+///
+/// The table is ordered from `Base` down to the bottom most sub-type, so a layer's index is
+/// its `depth()`. This lets `Base::layer_of` find a type without walking the hierarchy. It is
+/// kept alive by the owners of the object, `Cast` and the shared handles, and the object's
+/// `Base` only holds a weak reference to it.
+pub(crate) struct Ancestry {
+    layers: Vec<(TypeId, &'static str, usize)>
+}
+
+impl Ancestry {
+    /// Records the layers of `object`. Their offsets don't change when the object is moved.
+    ///
+    /// Every way of initializing an object goes through here, so this is also where it is
+    /// checked to implement its abstract virtual methods.
+    pub(crate) fn of(object: &dyn UnsafeCastable) -> Arc<Ancestry> {
+        check_virtuals(object);
+        let origin = object as *const dyn UnsafeCastable as *const u8 as usize;
+        let mut layers = Vec::new();
+        let mut layer = object;
+        loop {
+            let offset = layer as *const dyn UnsafeCastable as *const u8 as usize - origin;
+            layers.push((layer.get_ident(), layer.get_type_name(), offset));
+            if layer.get_ident() == Base::ident() {
+                break;
+            }
+            layer = layer.get_super();
+        }
+        layers.reverse();
+        Arc::new(Ancestry { layers })
+    }
+
+    /// Returns the byte offset, from the start of the object, of its layer of type `t`, which
+    /// is `depth` layers below `Base`.
+    pub(crate) fn offset_of(&self, t: TypeId, depth: usize) -> Option<usize> {
+        match self.layers.get(depth) {
+            Some(&(ident, _, offset)) if ident == t => Some(offset),
+            _ => None
+        }
+    }
+}
+
+/// Describes the bottom most sub-type of a correctly constructed object.
+///
+/// Stored in `Base`, and only ever created by `Constructable::init` and friends. It holds no
+/// pointer to the object, only a weak reference to its `Ancestry`, the type, name and offset
+/// of each of its layers. Layers are reached through the owners and guards holding the whole
+/// object, never from a `Base`.
+///
+/// A `Base` is only valid while the owners of its object are alive, and while it is still at
+/// the address the object was initialized at. A `Base` moved out of its object, e.g. by
+/// `mem::swap` on a super-type layer, is treated as uninitialized, even if it later ends up at
+/// that address again in another allocation.
+pub struct Instance {
+    origin: usize,
+    ancestry: Weak<Ancestry>
+}
+
+/// The root of every castable type hierarchy.
+///
+/// `Base` records where the rest of a correctly constructed object is, which is what makes
+/// downcasting possible. Its contents are private; create one with `Base::default()`.
+///
+/// ```compile_fail
+/// # use castable::Base;
+/// let base = Base { instance: None };
+/// ```
+#[derive(Default)]
 pub struct Base {
-    pub instance: Option<*mut UnsafeCastable>
+    instance: Option<Instance>
+}
+
+impl Base {
+    /// Lists the `TypeId` and name of `layer` and each of its super-types, ending with `Base`.
+    pub(crate) fn hierarchy_of(mut layer: &dyn UnsafeCastable) -> Vec<(TypeId, &'static str)> {
+        let mut hierarchy = Vec::new();
+        loop {
+            hierarchy.push((layer.get_ident(), layer.get_type_name()));
+            if layer.get_ident() == Base::ident() {
+                return hierarchy;
+            }
+            layer = layer.get_super();
+        }
+    }
+
+    /// Initializes the `Base` of the object at `object`, described by `ancestry`.
+    ///
+    /// Used by every correctly constructed owner (`Cast`, `RcCast`, `ArcCast`, `CastCell` and
+    /// `SyncCast`) once the object has reached its final location. The owner keeps `ancestry`
+    /// alive for as long as the object. `object` must be valid for writes, and no reference to
+    /// the object may be live.
+    pub(crate) unsafe fn init_instance<O>(object: *mut O, ancestry: &Arc<Ancestry>)
+        where O: ?Sized + UnsafeCastable
+    {
+        let origin = object as *mut u8 as usize;
+        let ancestry = Arc::downgrade(ancestry);
+        (*object).init_base(Some(Instance { origin, ancestry }));
+    }
+
+    /// Returns a pointer to the layer of `object` with the type `T`.
+    ///
+    /// The pointer is derived from `object` itself, so it keeps the owner's provenance.
+    /// `object` must point to a live object.
+    pub(crate) unsafe fn layer_of<T, O>(object: *mut O) -> Option<*mut T>
+        where T: UnsafeCastable, O: ?Sized + UnsafeCastable
+    {
+        if (*object).get_ident() == T::ident() {
+            return Some(object.cast());
+        }
+        let offset = (*object).get_base().offset_of(T::ident(), T::depth())?;
+        Some(object.cast::<u8>().add(offset).cast())
+    }
+
+    // The ancestry of the object this Base was initialized for, if the object's owners are
+    // still alive and the Base is still where it was then.
+    fn valid_ancestry(&self) -> Option<Arc<Ancestry>> {
+        let instance = self.instance.as_ref()?;
+        let ancestry = instance.ancestry.upgrade()?;
+        let origin = (self as *const Base as usize).wrapping_sub(ancestry.layers[0].2);
+        if origin == instance.origin { Some(ancestry) } else { None }
+    }
+
+    /// Returns true if this Base was initialized, and is still part of that object.
+    pub(crate) fn is_valid(&self) -> bool {
+        self.valid_ancestry().is_some()
+    }
+
+    /// Returns the `TypeId` of the bottom most sub-type, if this Base is initialized.
+    pub(crate) fn instance_ident(&self) -> Option<TypeId> {
+        self.valid_ancestry().and_then(|ancestry| ancestry.layers.last().map(|&(t, _, _)| t))
+    }
+
+    /// Returns the name of the bottom most sub-type, if this Base is initialized.
+    pub(crate) fn instance_name(&self) -> Option<&'static str> {
+        self.valid_ancestry().and_then(|ancestry| ancestry.layers.last().map(|&(_, n, _)| n))
+    }
+
+    /// Returns the `TypeId` and name of every layer, from the bottom most sub-type up to `Base`,
+    /// if this Base is initialized.
+    pub(crate) fn instance_hierarchy(&self) -> Option<Vec<(TypeId, &'static str)>> {
+        let ancestry = self.valid_ancestry()?;
+        Some(ancestry.layers.iter().rev().map(|&(t, n, _)| (t, n)).collect())
+    }
+
+    /// Returns whether the object has a layer of type `t`.
+    pub(crate) fn has_layer(&self, t: TypeId, depth: usize) -> bool {
+        self.offset_of(t, depth).is_some()
+    }
+
+    /// Returns the byte offset, from the start of the object, of its layer of type `t`.
+    fn offset_of(&self, t: TypeId, depth: usize) -> Option<usize> {
+        self.valid_ancestry()?.offset_of(t, depth)
+    }
 }
 
 impl Clone for Base {
     fn clone(&self) -> Self {
-        // we don't want to copy our instance pointer
-        Base { instance: None }
+        // we don't want to copy our instance
+        Base::default()
     }
 }
 
+/// Shows the bottom most sub-type of the object, or `None` if uninitialized.
 impl fmt::Debug for Base {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Base {{ ... }}")
+        f.debug_struct("Base")
+            .field("instance", &self.instance_name().map(TypeName))
+            .finish()
+    }
+}
+
+// Debug formats a type name without quotes.
+struct TypeName(&'static str);
+
+impl fmt::Debug for TypeName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.0)
     }
 }
 
-impl UnsafeCastable for Base {
-    fn init_base(&mut self, b: Option<*mut UnsafeCastable>) {
+unsafe impl UnsafeCastable for Base {
+    unsafe fn init_base(&mut self, b: Option<Instance>) {
         self.instance = b;
     }
 
@@ -31,28 +197,37 @@ impl UnsafeCastable for Base {
         TypeId::of::<Base>()
     }
 
+    fn depth() -> usize { 0 }
+
+    fn type_name() -> &'static str { "Base" }
+
+    fn hierarchy() -> Vec<(TypeId, &'static str)> {
+        vec![(Self::ident(), Self::type_name())]
+    }
+
     fn get_ident(&self) -> TypeId {
         Self::ident()
     }
 
-    fn get_super(&self) -> &UnsafeCastable { self }
-    fn get_super_mut(&mut self) -> &mut UnsafeCastable { self }
+    fn get_type_name(&self) -> &'static str {
+        Self::type_name()
+    }
+
+    fn get_super(&self) -> &dyn UnsafeCastable { self }
+    fn get_super_mut(&mut self) -> &mut dyn UnsafeCastable { self }
     fn get_base(&self) -> &Base { self }
     fn get_base_mut(&mut self) -> &mut Base { self }
-    fn as_any(&self) -> &Any { self }
-    fn as_any_mut(&mut self) -> &mut Any { self }
-    unsafe fn u_upcast(&self, _: TypeId) -> Option<&Any> { None }
-    unsafe fn u_upcast_mut(&mut self, _: TypeId) -> Option<&mut Any> { None }
-    unsafe fn u_downcast(&self, _: TypeId) -> Option<&Any> { None }
-    unsafe fn u_downcast_mut(&mut self, _: TypeId) -> Option<&mut Any> { None }
+    fn clone_dyn(&self) -> Option<Cast<Base>> { Some(self.clone().init()) }
+    // Base has no fields worth showing, so it never makes the output incomplete
+    fn debug_fields(&self, _: &mut fmt::DebugStruct) -> bool { true }
+    fn as_any(&self) -> &dyn Any { self }
+    fn as_any_mut(&mut self) -> &mut dyn Any { self }
+    fn as_dyn(&self) -> &dyn UnsafeCastable { self }
+    fn as_dyn_mut(&mut self) -> &mut dyn UnsafeCastable { self }
+    unsafe fn u_upcast(&self, _: TypeId) -> Option<&dyn Any> { None }
+    unsafe fn u_upcast_mut(&mut self, _: TypeId) -> Option<&mut dyn Any> { None }
 }
 
 impl Constructable for Base { type Super = Base; }
 
 impl Castable for Base {}
-
-impl Default for Base {
-    fn default() -> Self {
-        Base { instance: None }
-    }
-}
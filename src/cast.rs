@@ -1,35 +1,245 @@
 use Castable;
 use Constructable;
+use SubtypeOf;
 use UnsafeCastable;
+use {Handle, HandleMut};
 
-use std::marker::PhantomData;
+use base::{Ancestry, Base};
+use cast_error::CastError;
+
+use std::fmt;
+use std::mem;
+use std::ptr;
 use std::ops::{Deref, DerefMut};
+use std::slice;
+use std::sync::Arc;
 
-pub struct Cast<T: Castable> {
-    __box__: Box<UnsafeCastable>,
-    p: PhantomData<T>
+/// A correctly constructed castable object, viewed as `T`.
+///
+/// The object is owned through a raw pointer rather than a `Box`, and every layer pointer is
+/// derived from it. Moving a `Box` asserts unique access to its contents, which would
+/// invalidate the layer pointers; a raw pointer makes no such claim. The `Cast` also keeps the
+/// object's ancestry alive, which its `Base` refers to.
+///
+/// `O` is the trait-object type the object is stored as. `T` is only one layer of the object,
+/// so `T: Send` says nothing about its sub-types; instead a `Cast` is `Send` or `Sync` when
+/// `O` is. `Constructable::init_send()` and `init_sync()` create a
+/// `Cast<Self, dyn UnsafeCastable + Send>` and `Cast<Self, dyn UnsafeCastable + Send + Sync>`.
+///
+/// ```compile_fail
+/// # #[macro_use]
+/// # extern crate castable;
+/// # use castable::{Cast, Constructable};
+/// # inherit! {
+/// #     #[derive(Default)] struct SuperType;
+/// #     #[derive(Default)] struct SubType: SuperType;
+/// # }
+/// fn send<T: Send>(_: T) {}
+///
+/// # fn main() {
+/// // the sub-type could hold an Rc, so a plain Cast is not Send
+/// let super_type:Cast<SuperType> = SubType::default().init().upcast();
+/// send(super_type);
+/// # }
+/// ```
+// repr(C) keeps Cast<T> and Cast<U> layout compatible, see upcast_slice
+#[repr(C)]
+pub struct Cast<T: Castable, O: ?Sized + UnsafeCastable = dyn UnsafeCastable> {
+    __object__: *mut O,
+    ptr: *mut T,
+    ancestry: Arc<Ancestry>
+}
+
+unsafe impl<T: Castable, O: ?Sized + UnsafeCastable + Send> Send for Cast<T, O> {}
+unsafe impl<T: Castable, O: ?Sized + UnsafeCastable + Sync> Sync for Cast<T, O> {}
+
+impl<T: Castable, O: ?Sized + UnsafeCastable> Cast<T, O> {
+    /// Wraps a boxed castable object, viewing it as `T`.
+    ///
+    /// The object is initialized like `Constructable::init` does, so it can be downcast. The
+    /// `T` layer is looked up once, here, so dereferencing the `Cast` never downcasts. Panics
+    /// if the object has no `T` layer, if it is of an abstract type, or if it doesn't implement
+    /// an abstract virtual method of its super-types.
+    pub fn new(b: Box<O>) -> Self {
+        assert!(!b.is_abstract(), "Cast::new: {} is abstract", b.get_type_name());
+        let ancestry = Ancestry::of(b.as_dyn());
+        let object = Box::into_raw(b);
+        unsafe { Base::init_instance(object, &ancestry) };
+        match unsafe { Base::layer_of::<T, _>(object) } {
+            Some(ptr) => Cast::from_raw_parts(object, ptr, ancestry),
+            None => {
+                let b = unsafe { Box::from_raw(object) };
+                panic!("Cast::new: {}", CastError::new::<T>(b.get_base(), b.get_type_name()))
+            }
+        }
+    }
+
+    /// Wraps an object from `Box::into_raw` whose `T` layer is already known to be at `ptr`,
+    /// and whose `Base` was initialized with `ancestry`.
+    pub(crate) fn from_raw_parts(object: *mut O, ptr: *mut T, ancestry: Arc<Ancestry>) -> Self {
+        Cast { __object__: object, ptr, ancestry }
+    }
+
+    // Releases ownership of the object and its ancestry without dropping them.
+    fn into_raw(self) -> (*mut O, Arc<Ancestry>) {
+        let this = mem::ManuallyDrop::new(self);
+        (this.__object__, unsafe { ptr::read(&this.ancestry) })
+    }
+
+    /// Views the same object as `U`, which must be `T` or one of its super-types.
+    ///
+    /// The same as `upcast`. A sub-type the object may be isn't known at compile time, so
+    /// downcasts are made with `try_into_sub`, which hands the `Cast<T>` back on failure.
+    ///
+    /// ```compile_fail
+    /// # #[macro_use]
+    /// # extern crate castable;
+    /// # use castable::{Cast, Constructable};
+    /// # inherit! {
+    /// #     #[derive(Default)] struct SuperType;
+    /// #     #[derive(Default)] struct SubType: SuperType;
+    /// # }
+    /// # fn main() {
+    /// let super_type: Cast<SuperType> = SubType::default().init().upcast();
+    /// let sub_type: Cast<SubType> = super_type.cast_as();
+    /// # }
+    /// ```
+    pub fn cast_as<U: Castable>(self) -> Cast<U, O> where T: SubtypeOf<U> {
+        self.upcast()
+    }
+
+    /// Upcasts to a `Cast<U>` of the same object.
+    ///
+    /// Unlike `try_into_sub`, this can't fail: `T: SubtypeOf<U>` is checked at compile time.
+    pub fn upcast<U: Castable>(self) -> Cast<U, O> where T: SubtypeOf<U> {
+        let ptr = unsafe { Base::layer_of::<U, _>(self.__object__) }
+            .expect("SubtypeOf guarantees a U layer");
+        let (object, ancestry) = self.into_raw();
+        Cast::from_raw_parts(object, ptr, ancestry)
+    }
+
+    /// Reinterprets a `Vec<Cast<T>>` as a `Vec<Cast<U>>`, reusing its allocation and without
+    /// copying.
+    ///
+    /// Panics under the same conditions as `upcast_slice`.
+    pub fn upcast_vec<U: Castable>(v: Vec<Cast<T, O>>) -> Vec<Cast<U, O>> where T: SubtypeOf<U> {
+        if let Some(first) = v.first() {
+            first.assert_shared_layer::<U>();
+        }
+        let mut v = mem::ManuallyDrop::new(v);
+        unsafe { Vec::from_raw_parts(v.as_mut_ptr() as *mut Cast<U, O>, v.len(), v.capacity()) }
+    }
+
+    /// Reinterprets a slice of `Cast<T>` as a slice of `Cast<U>`, without copying.
+    ///
+    /// Types declared with `inherit!` are `#[repr(C)]` with their super-type first, so every
+    /// layer of an object starts at the same address. Panics if that isn't true of `T` and
+    /// `U`, which can only happen with `impl_inherit!` on a type that isn't `#[repr(C)]`.
+    ///
+    /// There is no mutable variant: writing a `Cast<U>` of a plain `U` into the slice would
+    /// leave a `Cast<T>` that has no `T` layer.
+    pub fn upcast_slice<U: Castable>(s: &[Cast<T, O>]) -> &[Cast<U, O>] where T: SubtypeOf<U> {
+        // the layout of T is static, so checking one element checks them all
+        if let Some(first) = s.first() {
+            first.assert_shared_layer::<U>();
+        }
+        unsafe { slice::from_raw_parts(s.as_ptr() as *const Cast<U, O>, s.len()) }
+    }
+
+    /// Borrows this `Cast<T>` as a `Cast<U>` of one of its super-types, without copying.
+    ///
+    /// Panics under the same conditions as `upcast_slice`.
+    pub fn as_super<U: Castable>(&self) -> &Cast<U, O> where T: SubtypeOf<U> {
+        self.assert_shared_layer::<U>();
+        unsafe { &*(self as *const Cast<T, O> as *const Cast<U, O>) }
+    }
+
+    /// Mutably borrows this `Cast<T>` as a `Cast<U>` of one of its super-types.
+    ///
+    /// Panics under the same conditions as `upcast_slice`.
+    ///
+    /// # Safety
+    ///
+    /// The returned `Cast<U>` must not be replaced, e.g. with `mem::swap` or by assignment.
+    /// Doing so could leave this `Cast<T>` owning an object with no `T` layer.
+    pub unsafe fn as_super_mut<U: Castable>(&mut self) -> &mut Cast<U, O>
+        where T: SubtypeOf<U>
+    {
+        self.assert_shared_layer::<U>();
+        &mut *(self as *mut Cast<T, O> as *mut Cast<U, O>)
+    }
+
+    // Cast<T> and Cast<U> are only interchangeable if the T and U layers share an address.
+    fn assert_shared_layer<U: Castable>(&self) where T: SubtypeOf<U> {
+        let u = unsafe { Base::layer_of::<U, _>(self.__object__) }
+            .expect("SubtypeOf guarantees a U layer");
+        assert!(u as *const u8 == self.ptr as *const u8,
+            "the super-type layer doesn't start at the sub-type layer");
+    }
+
+    /// Dynamically downcasts to a `Cast<U>` of the same object.
+    ///
+    /// Returns the original `Cast<T>` back if the object has no `U` layer.
+    pub fn try_into_sub<U: Castable>(self) -> Result<Cast<U, O>, Cast<T, O>> {
+        match unsafe { Base::layer_of::<U, _>(self.__object__) } {
+            Some(ptr) => {
+                let (object, ancestry) = self.into_raw();
+                Ok(Cast::from_raw_parts(object, ptr, ancestry))
+            }
+            None => Err(self)
+        }
+    }
 }
 
 impl<T: Castable> Cast<T> {
-    pub fn new(b: Box<UnsafeCastable>) -> Self {
-        Cast { __box__: b, p: PhantomData }
+    /// Clones the whole object, keeping its dynamic type and viewing the clone as `T`.
+    ///
+    /// Returns None if the bottom most sub-type doesn't implement `Clone`. Unlike cloning a
+    /// `&T`, which only clones the `T` layer, the clone can still be downcast to sub-types.
+    pub fn clone_dyn(&self) -> Option<Cast<T>> {
+        let object = unsafe { (*self.__object__).clone_dyn() }?;
+        object.try_into_sub().ok()
     }
+}
 
-    pub fn cast_as<U: Castable>(self) -> Cast<U> {
-        Cast::new(self.__box__)
+impl<T: Castable, O: ?Sized + UnsafeCastable> Drop for Cast<T, O> {
+    fn drop(&mut self) {
+        drop(unsafe { Box::from_raw(self.__object__) });
     }
 }
 
-impl<T: Castable> Deref for Cast<T> {
+impl<T: Castable, O: ?Sized + UnsafeCastable> Deref for Cast<T, O> {
     type Target = T;
     fn deref(&self) -> &T {
-        self.__box__.downcast().unwrap()
+        unsafe { &*self.ptr }
     }
 }
 
-impl<T: Castable> DerefMut for Cast<T> {
+impl<T: Castable, O: ?Sized + UnsafeCastable> DerefMut for Cast<T, O> {
     fn deref_mut(&mut self) -> &mut T {
-        self.__box__.downcast_mut().unwrap()
+        unsafe { &mut *self.ptr }
+    }
+}
+
+unsafe impl<T: Castable, O: ?Sized + UnsafeCastable> Handle for Cast<T, O> {
+    fn object(&self) -> &dyn UnsafeCastable {
+        unsafe { (*self.__object__).as_dyn() }
+    }
+}
+
+unsafe impl<T: Castable, O: ?Sized + UnsafeCastable> HandleMut for Cast<T, O> {
+    fn object_mut(&mut self) -> &mut dyn UnsafeCastable {
+        unsafe { (*self.__object__).as_dyn_mut() }
+    }
+}
+
+/// Formats the bottom most sub-type, with the fields of every layer flattened, e.g.
+/// `Salesperson { name: "John", hours: 12.5, pay: 15.25, sales: 4 }`.
+///
+/// See `UnsafeCastable::fmt_flat`.
+impl<T: Castable, O: ?Sized + UnsafeCastable> fmt::Debug for Cast<T, O> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        unsafe { (*self.__object__).fmt_flat(f) }
     }
 }
 
@@ -0,0 +1,89 @@
+use base::{Ancestry, Base};
+use shared_cast::Shared;
+use Castable;
+use Constructable;
+use UnsafeCastable;
+use {Handle, HandleMut};
+
+use std::cell::{BorrowError, BorrowMutError, Ref, RefCell, RefMut};
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+
+locked_cast! {
+    /// A correctly constructed, reference-counted castable object with interior mutability.
+    ///
+    /// Created by `Constructable::init_cell()`. Borrows are tracked for the whole object like a
+    /// `RefCell`, so a mutable borrow through a `CastCell<Person>` blocks a shared borrow through
+    /// a `CastCell<Salesperson>` of the same object. The guards returned by `borrow()` and
+    /// `borrow_mut()` deref to `T`, and are handles to the whole object, so they can be downcast.
+    ///
+    /// ```
+    /// # #![allow(dead_code)]
+    /// # #[macro_use]
+    /// # extern crate castable;
+    /// # use castable::{CastCell, Constructable, HandleMut};
+    /// # inherit! {
+    /// #     #[derive(Default)] struct SuperType { count: u32 }
+    /// #     #[derive(Default)] struct SubType: SuperType { sub_count: u32 }
+    /// # }
+    /// # fn main() {
+    /// let super_type:CastCell<SuperType> = SubType::default().init_cell().cast().unwrap();
+    /// let sub_type = super_type.cast::<SubType>().unwrap();
+    /// {
+    ///     let mut guard = super_type.borrow_mut();
+    ///     guard.count += 1;
+    ///     guard.downcast_mut::<SubType>().unwrap().sub_count += 1;
+    ///     // the whole object is borrowed, whichever view it was borrowed through
+    ///     assert!(sub_type.try_borrow().is_err());
+    /// }
+    /// assert_eq!(sub_type.borrow().sub_count, 1);
+    /// # }
+    /// ```
+    CastCell, Rc, RefCell<dyn UnsafeCastable>, PhantomData(*const T);
+
+    /// A shared borrow of a `CastCell<T>`, which derefs to `T`.
+    CastRef, Ref;
+
+    /// A mutable borrow of a `CastCell<T>`, which derefs to `T`.
+    CastRefMut, RefMut
+}
+
+impl<T: Castable> CastCell<T> {
+    pub(crate) fn new(t: T) -> Self where T: Constructable {
+        let mut shared = Rc::new(Shared { ancestry: Ancestry::of(&t), object: RefCell::new(t) });
+        {
+            let shared = Rc::get_mut(&mut shared).unwrap();
+            unsafe { Base::init_instance(shared.object.get_mut(), &shared.ancestry) };
+        }
+        CastCell { __shared__: shared, offset: 0, p: PhantomData }
+    }
+
+    /// Immutably borrows the object, panicking if it is mutably borrowed.
+    pub fn borrow(&self) -> CastRef<'_, T> {
+        self.try_borrow().expect("CastCell: already mutably borrowed")
+    }
+
+    /// Mutably borrows the object, panicking if it is borrowed.
+    pub fn borrow_mut(&self) -> CastRefMut<'_, T> {
+        self.try_borrow_mut().expect("CastCell: already borrowed")
+    }
+
+    /// Immutably borrows the object, returning an error if it is mutably borrowed.
+    pub fn try_borrow(&self) -> Result<CastRef<'_, T>, BorrowError> {
+        let guard = self.__shared__.object.try_borrow()?;
+        Ok(CastRef { guard, offset: self.offset, p: PhantomData })
+    }
+
+    /// Mutably borrows the object, returning an error if it is borrowed.
+    pub fn try_borrow_mut(&self) -> Result<CastRefMut<'_, T>, BorrowMutError> {
+        let guard = self.__shared__.object.try_borrow_mut()?;
+        Ok(CastRefMut { guard, offset: self.offset, p: PhantomData })
+    }
+}
+
+impl<T: Constructable> From<T> for CastCell<T> {
+    fn from(t: T) -> Self {
+        CastCell::new(t)
+    }
+}
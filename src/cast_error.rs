@@ -0,0 +1,61 @@
+use Base;
+use UnsafeCastable;
+
+use std::error::Error;
+use std::fmt;
+
+/// The reason a downcast failed.
+///
+/// A downcast fails either because the object is a different type, or because its `Base` no
+/// longer describes it, having been moved out of it, e.g. by `mem::swap` on a layer. The
+/// `Display` message tells the two apart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CastError {
+    requested: &'static str,
+    actual: &'static str,
+    uninitialized: bool
+}
+
+impl CastError {
+    /// Describes a failed cast to `T` of the object `base` belongs to.
+    ///
+    /// `layer` is the name of the type being cast from, used when the object is uninitialized.
+    pub(crate) fn new<T: UnsafeCastable>(base: &Base, layer: &'static str) -> Self {
+        let instance = base.instance_name();
+        CastError {
+            requested: T::type_name(),
+            actual: instance.unwrap_or(layer),
+            uninitialized: instance.is_none()
+        }
+    }
+
+    /// Returns the name of the type the cast was to.
+    pub fn requested(&self) -> &'static str {
+        self.requested
+    }
+
+    /// Returns the name of the object's bottom most sub-type.
+    ///
+    /// If the object is uninitialized, this is the type the cast was from instead.
+    pub fn actual(&self) -> &'static str {
+        self.actual
+    }
+
+    /// Returns true if the object's `Base` had no valid instance, i.e. it has been moved out of
+    /// its object.
+    pub fn is_uninitialized(&self) -> bool {
+        self.uninitialized
+    }
+}
+
+impl fmt::Display for CastError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cannot cast {} to {}", self.actual, self.requested)?;
+        if self.uninitialized {
+            write!(f, ": the object's Base was moved out of it")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for CastError {}
@@ -1,22 +1,24 @@
 use UnsafeCastable;
+use base::Base;
+use layers::{Layers, LayersMut};
 
-/// A safe implementation for downcasting using type parameters.
+use std::any::TypeId;
+
+/// Safe queries on a layer of a castable object.
 ///
 /// Types inheriting from `Base` can be casted up and down. Upcasting is automatic through
-/// the use of `Deref`; downcasting is explicit through the use of the `downcast` method defined
-/// on this trait. Types created using the `inherit!` or `impl_inherit!` macros will implement
-/// this trait for you.
+/// the use of `Deref`. Downcasting reaches the rest of the object, so it is only done through
+/// the owners and guards holding the whole object; see `Handle`. Types created using the
+/// `inherit!` or `impl_inherit!` macros will implement this trait for you.
 ///
-/// Downcasting is dynamcic, and will fail by returning None if the downcast cannot be completed.
-/// The return type is `Option<T>`, so it is advised to use a match expression instead of
-/// `unwrap()` unless you are confident the downcast won't fail.
+/// A layer can still tell what the object it is part of is, from the ancestry table recorded
+/// in `Base` by `Constructable::init`:
 ///
 /// ```
 /// # #![allow(dead_code)]
 /// # #[macro_use]
 /// # extern crate castable;
-/// # use castable::Castable;
-/// # use castable::Constructable;
+/// # use castable::{Castable, Constructable, Handle};
 /// # inherit! {
 /// #     #[derive(Default, Clone)] struct SuperType;
 /// #     #[derive(Default, Clone)] struct SubType: SuperType;
@@ -27,33 +29,84 @@ use UnsafeCastable;
 /// let super_type:&SuperType = sub_type;
 /// // an explicit upcast using the upcast! macro
 /// let super_type = upcast!(sub_type as SuperType);
-/// // an explicit downcast
-/// assert!(super_type.downcast::<SubType>().is_some());
+/// assert!(super_type.is::<SubType>());
+/// assert_eq!(super_type.dynamic_type_name(), "SubType");
+/// // an explicit downcast, through the Cast holding the whole object
+/// assert!(sub_type.downcast::<SubType>().is_some());
 /// # }
 /// ```
 ///
-/// Downcasting will only work if a type is correctly constructed, i.e. wrapped in a `Cast<T>`
-/// object. Consult the documentation for the `Constructable` trait and `Cast` type for details
-/// on correctly constructed types.
+/// Only correctly constructed objects, i.e. wrapped in a `Cast<T>` or another owner, know
+/// their sub-types. Consult the documentation for the `Constructable` trait and `Cast` type
+/// for details on correctly constructed types.
+///
+/// Moving a layer out of its object, e.g. with `mem::swap` or `mem::replace` on a `&mut` to a
+/// super-type, leaves both the moved layer and the object it came from incorrectly constructed:
+/// they answer for their own layers only, rather than for the wrong object.
 pub trait Castable: UnsafeCastable {
-    /// Dynamically downcasts Self to T, returning None on failure.
-    fn downcast<T: UnsafeCastable>(&self) -> Option<&T> {
-        if let Some(any) = unsafe { self.u_downcast(T::ident()) } {
-            any.downcast_ref::<T>()
-        } else {
-            None
+    /// Returns true if the object Self is part of was correctly constructed.
+    ///
+    /// Objects built with `construct!(raw ...)`, a struct expression or `clone()` are not, and
+    /// know nothing of their sub-types until `Constructable::init` is called on them.
+    fn is_initialized(&self) -> bool {
+        self.get_base().is_valid()
+    }
+
+    /// Returns true if the object Self is part of is a `T`, or a sub-type of `T`.
+    ///
+    /// Equivalent to a handle's `downcast::<T>().is_some()`. Only Self and its super-types are
+    /// checked if the object isn't correctly constructed.
+    fn is<T: UnsafeCastable>(&self) -> bool {
+        if self.is_initialized() {
+            return self.get_base().has_layer(T::ident(), T::depth());
         }
+        Layers::new(self.as_dyn(), Base::ident()).any(|layer| layer.get_ident() == T::ident())
+    }
+
+    /// Returns true if the bottom most sub-type of the object Self is part of is `T`.
+    fn is_exactly<T: UnsafeCastable>(&self) -> bool {
+        self.dynamic_type_id() == T::ident()
+    }
+
+    /// Returns the `TypeId` of the bottom most sub-type of the object Self is part of.
+    ///
+    /// Falls back to the `TypeId` of Self if the object isn't correctly constructed.
+    fn dynamic_type_id(&self) -> TypeId {
+        self.get_base().instance_ident().unwrap_or_else(|| self.get_ident())
     }
 
-    /// Dynamically downcasts Self to T, returning None on failure.
-    fn downcast_mut<T: UnsafeCastable>(&mut self) -> Option<&mut T> {
-        if let Some(any) = unsafe { self.u_downcast_mut(T::ident()) } {
-            any.downcast_mut::<T>()
-        } else {
-            None
+    /// Returns the name of the bottom most sub-type of the object Self is part of.
+    ///
+    /// Falls back to the name of Self if the object isn't correctly constructed.
+    fn dynamic_type_name(&self) -> &'static str {
+        self.get_base().instance_name().unwrap_or_else(|| self.get_type_name())
+    }
+
+    /// Returns the `TypeId` and name of every layer of the object Self is part of, from its
+    /// bottom most sub-type up to `Base`.
+    ///
+    /// Starts from Self if the object isn't correctly constructed.
+    fn dynamic_hierarchy(&self) -> Vec<(TypeId, &'static str)> {
+        if let Some(hierarchy) = self.get_base().instance_hierarchy() {
+            return hierarchy;
         }
+        let mut hierarchy = vec![(self.get_ident(), self.get_type_name())];
+        if self.get_ident() != Base::ident() {
+            hierarchy.extend(Base::hierarchy_of(self.get_super()));
+        }
+        hierarchy
+    }
+
+    /// Iterates over the layers of the object, from Self up to `Base`.
+    fn ancestors(&self) -> Layers<'_> where Self: Sized {
+        Layers::new(self, Base::ident())
+    }
+
+    /// Mutable variant of the `ancestors` method.
+    fn ancestors_mut(&mut self) -> LayersMut<'_> where Self: Sized {
+        LayersMut::new(self, Base::ident())
     }
 }
 
 /// Implement `Castable` for the `UnsafeCastable` trait-object.
-impl Castable for UnsafeCastable {}
+impl Castable for dyn UnsafeCastable {}
@@ -0,0 +1,34 @@
+// Lets the macros implement `UnsafeCastable::clone_dyn` whether or not a type is `Clone`.
+//
+// `(&CloneWrap(&value)).clone_cast()` resolves to `CloneViaClone` when the concrete type is
+// `Clone`, since its receiver needs no extra auto-ref, and falls back to `CloneViaNone`
+// otherwise. This only works where the type is concrete, i.e. inside a macro expansion.
+use Base;
+use Cast;
+use Constructable;
+use SubtypeOf;
+
+#[doc(hidden)]
+pub struct CloneWrap<'a, T: 'a>(pub &'a T);
+
+#[doc(hidden)]
+pub trait CloneViaClone {
+    fn clone_cast(&self) -> Option<Cast<Base>>;
+}
+
+impl<'a, T: Clone + Constructable + SubtypeOf<Base>> CloneViaClone for CloneWrap<'a, T> {
+    fn clone_cast(&self) -> Option<Cast<Base>> {
+        Some(self.0.clone().init().upcast())
+    }
+}
+
+#[doc(hidden)]
+pub trait CloneViaNone {
+    fn clone_cast(&self) -> Option<Cast<Base>>;
+}
+
+impl<'a, 'b, T> CloneViaNone for &'b CloneWrap<'a, T> {
+    fn clone_cast(&self) -> Option<Cast<Base>> {
+        None
+    }
+}
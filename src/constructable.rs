@@ -1,19 +1,22 @@
+use Base;
 use Cast;
 use Castable;
+use {ArcCast, CastCell, RcCast, SyncCast};
 use UnsafeCastable;
+use base::Ancestry;
 
 /// Methods to ensure the correct initialization of types inheriting from `Base`.
 ///
 /// Types can be constructed without being wrapped in `Cast<T>` objects by using struct
 /// expressions manually and with the `construct!( raw ... )` macro. However, types constructed
-/// this way are downcast succeeded; the `downcast::<T>()` method will always return None.
+/// this way are incorrectly constructed: they know nothing of their sub-types, and there is no
+/// handle to downcast them through.
 ///
 /// ```
 /// # #![allow(dead_code)]
 /// # #[macro_use]
 /// # extern crate castable;
-/// # use castable::Castable;
-/// # use castable::Constructable;
+/// # use castable::{Castable, Constructable, Handle};
 /// # inherit! {
 /// #     #[derive(Default)] struct SuperType;
 /// #     #[derive(Default)] struct SubType: SuperType;
@@ -22,14 +25,15 @@ use UnsafeCastable;
 /// // a correctly constructed type (call init(), converts to Cast<SubType>)
 /// let sub_type = &SubType::default().init();
 /// let super_type:&SuperType = sub_type;
+/// assert!(super_type.is::<SubType>());
 /// // downcast succeeded
-/// assert!(super_type.downcast::<SubType>().is_some());
+/// assert!(sub_type.downcast::<SubType>().is_some());
 ///
 /// // an incorrectly constructed type (not init(), unconverted SubType)
 /// let sub_type = &SubType::default();
+/// assert!(!sub_type.is_initialized());
 /// let super_type:&SuperType = sub_type;
-/// // downcast failed
-/// assert!(super_type.downcast::<SubType>().is_none());
+/// assert!(!super_type.is::<SubType>());
 /// # }
 /// ```
 ///
@@ -37,13 +41,14 @@ use UnsafeCastable;
 /// ends up creating an incorrectly constructed type, which will the same limitations mentioned
 /// above. If you want to create a correctly constructed clone of a type, call `clone().init()`.
 /// It should be noted that calling `clone().init()` will not create a clone of the bottom level
-/// subtype, but a clone of the currently casted type.
+/// subtype, but a clone of the currently casted type. `Cast::clone_dyn()` clones the bottom
+/// level subtype instead, whatever type the `Cast` is viewed as.
 ///
 /// ```
 /// # #![allow(dead_code)]
 /// # #[macro_use]
 /// # extern crate castable;
-/// # use castable::Castable;
+/// # use castable::{Cast, Castable, Handle};
 /// # use castable::Constructable;
 /// # inherit! {
 /// #     #[derive(Default, Clone)] struct SuperType;
@@ -59,26 +64,84 @@ use UnsafeCastable;
 /// assert!(super_type.downcast::<SubType>().is_none());
 ///
 /// // expected behavior is achieved by downcasting before calling clone
-/// let super_type:&SuperType = &sub_type.downcast::<SubType>().unwrap().clone().init();
+/// let super_type:Cast<SuperType> = sub_type.downcast::<SubType>().unwrap().clone().init()
+///     .upcast();
 /// // downcast succeeded
 /// assert!(super_type.downcast::<SubType>().is_some());
+///
+/// // or, clone the whole object through the Cast
+/// let super_type:Cast<SuperType> = SubType::default().init().upcast();
+/// let super_type = super_type.clone_dyn().unwrap();
+/// assert!(super_type.downcast::<SubType>().is_some());
 /// # }
 /// ```
 pub trait Constructable: Castable where Self: 'static {
     /// The super-type that `Self` inherits from.
     ///
-    /// Used by the `construct!` macro to call the super-type's `Default::default()` when the
-    /// value of the super-type is omitted.
-    type Super: Constructable;
+    /// It may be abstract, i.e. not `Constructable` itself.
+    type Super: Castable;
 
     /// Wraps an incorrectly constructed type in a `Cast<Self>` object.
     ///
     /// As mentioned above, this method converts an incorrectly constructed type into a correctly
-    /// constructed type, allowing downcasting.
+    /// constructed type, allowing downcasting. The offset of every layer is recorded here,
+    /// once, so that later downcasts don't need to walk the hierarchy.
+    ///
+    /// Panics if `Self` doesn't implement an abstract virtual method of its super-types, as do
+    /// the other `init` methods.
     fn init(self) -> Cast<Self> where Self: Sized {
-        let b:Box<UnsafeCastable> = Box::new(self);
-        let bp = Box::into_raw(b);
-        unsafe { &mut *bp }.init_base(Some(bp));
-        Cast::new(unsafe { Box::from_raw(bp) })
+        let ancestry = Ancestry::of(&self);
+        let bp = Box::into_raw(Box::new(self));
+        unsafe { Base::init_instance(bp, &ancestry) };
+        Cast::from_raw_parts(bp as *mut dyn UnsafeCastable, bp, ancestry)
+    }
+
+    /// Wraps an incorrectly constructed type in a `Cast` that can be sent between threads.
+    fn init_send(self) -> Cast<Self, dyn UnsafeCastable + Send> where Self: Sized + Send {
+        let ancestry = Ancestry::of(&self);
+        let bp = Box::into_raw(Box::new(self));
+        unsafe { Base::init_instance(bp, &ancestry) };
+        Cast::from_raw_parts(bp as *mut (dyn UnsafeCastable + Send), bp, ancestry)
+    }
+
+    /// Wraps an incorrectly constructed type in a `Cast` that can be sent and shared between
+    /// threads.
+    fn init_sync(self) -> Cast<Self, dyn UnsafeCastable + Send + Sync>
+        where Self: Sized + Send + Sync
+    {
+        let ancestry = Ancestry::of(&self);
+        let bp = Box::into_raw(Box::new(self));
+        unsafe { Base::init_instance(bp, &ancestry) };
+        Cast::from_raw_parts(bp as *mut (dyn UnsafeCastable + Send + Sync), bp, ancestry)
     }
+
+    /// Wraps an incorrectly constructed type in a reference-counted `RcCast<Self>` object.
+    fn init_rc(self) -> RcCast<Self> where Self: Sized {
+        RcCast::new_rc(self)
+    }
+
+    /// Wraps an incorrectly constructed type in a `CastCell<Self>`, a reference-counted handle
+    /// with interior mutability.
+    fn init_cell(self) -> CastCell<Self> where Self: Sized {
+        CastCell::new(self)
+    }
+
+    /// Wraps an incorrectly constructed type in an atomically reference-counted
+    /// `ArcCast<Self>` object.
+    fn init_arc(self) -> ArcCast<Self> where Self: Sized + Send + Sync {
+        ArcCast::new_arc(self)
+    }
+
+    /// Wraps an incorrectly constructed type in a `SyncCast<Self>`, which shares it between
+    /// threads behind a `RwLock`.
+    fn init_rwlock(self) -> SyncCast<Self> where Self: Sized + Send + Sync {
+        SyncCast::new(self)
+    }
+}
+
+/// Returns `t` unchanged, so that `construct!( raw .. )` only compiles for types that aren't
+/// abstract.
+#[doc(hidden)]
+pub fn raw<T: Constructable>(t: T) -> T {
+    t
 }
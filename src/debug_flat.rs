@@ -0,0 +1,31 @@
+// Lets the macros implement `UnsafeCastable::debug_fields` whether or not a type is `Debug`.
+//
+// `(&DebugWrap(&value)).as_debug()` resolves to `DebugViaDebug` when the concrete type is
+// `Debug`, and falls back to `DebugViaNone` otherwise, the same way as `CloneWrap`. It is used
+// both to check that a layer has its own `Debug`, and to format each of its fields.
+use std::fmt;
+
+#[doc(hidden)]
+pub struct DebugWrap<'a, T: 'a>(pub &'a T);
+
+#[doc(hidden)]
+pub trait DebugViaDebug {
+    fn as_debug(&self) -> Option<&dyn fmt::Debug>;
+}
+
+impl<'a, T: fmt::Debug> DebugViaDebug for DebugWrap<'a, T> {
+    fn as_debug(&self) -> Option<&dyn fmt::Debug> {
+        Some(self.0)
+    }
+}
+
+#[doc(hidden)]
+pub trait DebugViaNone {
+    fn as_debug(&self) -> Option<&dyn fmt::Debug>;
+}
+
+impl<'a, 'b, T> DebugViaNone for &'b DebugWrap<'a, T> {
+    fn as_debug(&self) -> Option<&dyn fmt::Debug> {
+        None
+    }
+}
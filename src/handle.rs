@@ -0,0 +1,121 @@
+use Castable;
+use UnsafeCastable;
+use base::Base;
+use cast_error::CastError;
+use layers::{Layers, LayersMut};
+use sealed::Sealed;
+
+use std::ops::{Deref, DerefMut};
+
+/// An owner or guard holding a whole correctly constructed object, viewed as one of its layers.
+///
+/// Downcasting reaches the rest of the object, so it needs a pointer to all of it; a `&T` only
+/// covers the `T` layer, and widening it to the whole object would be undefined behavior. This
+/// trait is implemented by the types that hold such a pointer: `Cast`, `RcCast`, `ArcCast`,
+/// and the guards of `CastCell` and `SyncCast`. `HandleMut` adds the mutable variants.
+///
+/// ```
+/// # #![allow(dead_code)]
+/// # #[macro_use]
+/// # extern crate castable;
+/// # use castable::{Cast, Constructable, Handle};
+/// # inherit! {
+/// #     #[derive(Default)] struct SuperType;
+/// #     #[derive(Default)] struct SubType: SuperType;
+/// # }
+/// # fn main() {
+/// let super_type:Cast<SuperType> = SubType::default().init().upcast();
+/// let sub_type:&SubType = super_type.downcast().unwrap();
+/// # }
+/// ```
+///
+/// # Safety
+///
+/// `object` must return the whole object the handle derefs into, borrowed for as long as the
+/// handle is.
+pub unsafe trait Handle: Deref {
+    /// Returns the whole object.
+    #[doc(hidden)]
+    fn object(&self) -> &dyn UnsafeCastable;
+
+    /// Dynamically downcasts to a `U` layer of the object, returning None on failure.
+    ///
+    /// Looks `U` up in the ancestry table recorded by `Constructable::init`, which takes
+    /// constant time regardless of how deep the hierarchy is.
+    fn downcast<U: Castable>(&self) -> Option<&U> {
+        let object = self.object();
+        warn_uninitialized::<U>(object);
+        let ptr = unsafe { Base::layer_of::<U, _>(object as *const _ as *mut dyn UnsafeCastable) };
+        ptr.map(|p| unsafe { &*p })
+    }
+
+    /// Dynamically downcasts to a `U` layer of the object, describing why on failure.
+    fn try_downcast<U: Castable>(&self) -> Result<&U, CastError> {
+        self.downcast().ok_or_else(|| {
+            let object = self.object();
+            CastError::new::<U>(object.get_base(), object.get_type_name())
+        })
+    }
+
+    /// Iterates over the layers of the object, from its bottom most sub-type up to the layer
+    /// the handle is viewed as.
+    fn descendants_view(&self) -> Layers<'_> where Self::Target: Castable + Sized {
+        Layers::new(self.object(), <Self::Target as UnsafeCastable>::ident())
+    }
+
+    /// Returns the object as its bottom most sub-type, for an exhaustive `match`.
+    ///
+    /// Only available for types declared in a `sealed` block of `inherit!`.
+    fn kind(&self) -> <Self::Target as Sealed>::Kind<'_> where Self::Target: Sealed {
+        <Self::Target as Sealed>::kind_of(self.object())
+    }
+}
+
+/// The mutable variant of `Handle`, implemented by `Cast`, `CastRefMut` and
+/// `SyncCastWriteGuard`.
+///
+/// # Safety
+///
+/// `object_mut` must return the whole object the handle derefs into, borrowed for as long as
+/// the handle is.
+pub unsafe trait HandleMut: Handle + DerefMut {
+    /// Mutable variant of `object`.
+    #[doc(hidden)]
+    fn object_mut(&mut self) -> &mut dyn UnsafeCastable;
+
+    /// Dynamically downcasts to a mutable `U` layer of the object, returning None on failure.
+    fn downcast_mut<U: Castable>(&mut self) -> Option<&mut U> {
+        let object = self.object_mut();
+        warn_uninitialized::<U>(object);
+        let ptr = unsafe { Base::layer_of::<U, _>(object as *mut dyn UnsafeCastable) };
+        ptr.map(|p| unsafe { &mut *p })
+    }
+
+    /// Dynamically downcasts to a mutable `U` layer of the object, describing why on failure.
+    fn try_downcast_mut<U: Castable>(&mut self) -> Result<&mut U, CastError> {
+        if self.downcast::<U>().is_none() {
+            let object = self.object();
+            return Err(CastError::new::<U>(object.get_base(), object.get_type_name()));
+        }
+        Ok(self.downcast_mut().expect("checked by downcast"))
+    }
+
+    /// Mutable variant of the `descendants_view` method.
+    fn descendants_view_mut(&mut self) -> LayersMut<'_> where Self::Target: Castable + Sized {
+        LayersMut::new(self.object_mut(), <Self::Target as UnsafeCastable>::ident())
+    }
+
+    /// Mutable variant of the `kind` method.
+    fn kind_mut(&mut self) -> <Self::Target as Sealed>::KindMut<'_> where Self::Target: Sealed {
+        <Self::Target as Sealed>::kind_of_mut(self.object_mut())
+    }
+}
+
+// A downcast on an uninitialized object always fails, which is easy to mistake for the object
+// being the wrong type. Debug builds log a warning.
+fn warn_uninitialized<T: UnsafeCastable>(object: &dyn UnsafeCastable) {
+    let base = object.get_base();
+    if cfg!(debug_assertions) && !base.is_valid() && object.get_ident() != T::ident() {
+        warn!("downcast failed, {}", CastError::new::<T>(base, object.get_type_name()));
+    }
+}
@@ -0,0 +1,82 @@
+use Base;
+use UnsafeCastable;
+
+use std::any::TypeId;
+
+/// An iterator over the layers of an object, from a sub-type up to one of its super-types.
+///
+/// Created by `Castable::ancestors` and `Handle::descendants_view`.
+pub struct Layers<'a> {
+    next: Option<&'a dyn UnsafeCastable>,
+    last: TypeId
+}
+
+impl<'a> Layers<'a> {
+    // `last` is the TypeId of the final layer; the walk always ends at `Base` regardless.
+    pub(crate) fn new(first: &'a dyn UnsafeCastable, last: TypeId) -> Self {
+        Layers { next: Some(first), last }
+    }
+}
+
+impl<'a> Iterator for Layers<'a> {
+    type Item = &'a dyn UnsafeCastable;
+
+    fn next(&mut self) -> Option<&'a dyn UnsafeCastable> {
+        let layer = self.next.take()?;
+        if layer.get_ident() != self.last && layer.get_ident() != Base::ident() {
+            self.next = Some(layer.get_super());
+        }
+        Some(layer)
+    }
+}
+
+/// Mutable variant of `Layers`.
+///
+/// Every layer contains the layers after it, so only one may be borrowed at a time. Rather than
+/// implementing `Iterator`, `next_layer` borrows the `LayersMut` itself:
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate castable;
+/// # use castable::{Castable, Constructable};
+/// # inherit! {
+/// #     #[derive(Default)] struct SuperType;
+/// #     #[derive(Default)] struct SubType: SuperType;
+/// # }
+/// # fn main() {
+/// let mut sub_type = SubType::default().init();
+/// let mut layers = sub_type.ancestors_mut();
+/// let mut names = Vec::new();
+/// while let Some(layer) = layers.next_layer() {
+///     names.push(layer.get_type_name());
+/// }
+/// assert_eq!(names, ["SubType", "SuperType", "Base"]);
+/// # }
+/// ```
+pub struct LayersMut<'a> {
+    next: Option<&'a mut dyn UnsafeCastable>,
+    last: TypeId,
+    started: bool
+}
+
+impl<'a> LayersMut<'a> {
+    pub(crate) fn new(first: &'a mut dyn UnsafeCastable, last: TypeId) -> Self {
+        LayersMut { next: Some(first), last, started: false }
+    }
+
+    /// Moves on to the next layer and returns it, or None once the last layer has been seen.
+    pub fn next_layer(&mut self) -> Option<&mut dyn UnsafeCastable> {
+        if self.started {
+            let layer = self.next.take()?;
+            if layer.get_ident() == self.last || layer.get_ident() == Base::ident() {
+                return None;
+            }
+            self.next = Some(layer.get_super_mut());
+        }
+        self.started = true;
+        match self.next {
+            Some(ref mut layer) => Some(&mut **layer),
+            None => None
+        }
+    }
+}
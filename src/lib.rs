@@ -1,19 +1,56 @@
 //#![warn(missing_docs)]
 
+#[macro_use]
+extern crate log;
+
 #[macro_use]
 pub mod macros;
+#[macro_use]
+mod locked_cast;
 
 mod base;
 mod cast;
+mod cast_cell;
+mod cast_error;
 mod castable;
+mod clone_dyn;
 mod constructable;
+mod debug_flat;
+mod handle;
+mod layers;
+mod sealed;
+mod shared_cast;
+mod subtype_of;
+mod sync_cast;
 mod unsafe_castable;
+mod virtual_fn;
 
-pub use base::Base;
+pub use base::{Base, Instance};
 pub use cast::Cast;
+pub use cast_cell::{CastCell, CastRef, CastRefMut};
+pub use cast_error::CastError;
 pub use castable::Castable;
+#[doc(hidden)]
+pub use clone_dyn::{CloneViaClone, CloneViaNone, CloneWrap};
 pub use constructable::Constructable;
+#[doc(hidden)]
+pub use constructable::raw;
+#[doc(hidden)]
+pub use debug_flat::{DebugViaDebug, DebugViaNone, DebugWrap};
+pub use handle::{Handle, HandleMut};
+pub use layers::{Layers, LayersMut};
+pub use sealed::Sealed;
+#[doc(hidden)]
+pub use sealed::Inheritable;
+pub use shared_cast::{ArcCast, RcCast, WeakArcCast, WeakCast};
+pub use subtype_of::SubtypeOf;
+pub use sync_cast::{SyncCast, SyncCastReadGuard, SyncCastWriteGuard};
 pub use unsafe_castable::UnsafeCastable;
+#[doc(hidden)]
+pub use virtual_fn::{find_virtual, has_virtual, virtual_key};
+#[doc(hidden)]
+pub use virtual_fn::{SuperLayer, VirtualBody, VirtualBodyMut};
+pub use virtual_fn::VirtualSelf;
 
 #[cfg(test)]
 pub mod tests;
@@ -0,0 +1,93 @@
+// CastCell and SyncCast only differ in how they lock the object, so both handles and their
+// guards are generated here. The locking methods themselves are written out in each module.
+macro_rules! locked_cast {
+    ($(#[$doc:meta])* $name:ident, $ptr:ident, $lock:ident<$obj:ty>, PhantomData($marker:ty);
+     $(#[$rdoc:meta])* $read:ident, $rguard:ident;
+     $(#[$wdoc:meta])* $write:ident, $wguard:ident) => {
+        $(#[$doc])*
+        pub struct $name<T: Castable> {
+            __shared__: $ptr<Shared<$lock<$obj>>>,
+            // the offset of the T layer, rather than a pointer, so it's derived from each guard
+            offset: usize,
+            p: PhantomData<$marker>
+        }
+
+        impl<T: Castable> $name<T> {
+            /// Dynamically casts to another shared handle of the same object, up or down,
+            /// returning None on failure.
+            ///
+            /// The `U` layer is found in the ancestry the handle shares with the object, so the
+            /// object isn't locked, and this succeeds whoever holds the lock.
+            pub fn cast<U: Castable>(&self) -> Option<$name<U>> {
+                let offset = self.__shared__.ancestry.offset_of(U::ident(), U::depth())?;
+                Some($name { __shared__: self.__shared__.clone(), offset, p: PhantomData })
+            }
+
+            /// Returns true if both handles share the same object.
+            pub fn ptr_eq(this: &Self, other: &$name<T>) -> bool {
+                $ptr::ptr_eq(&this.__shared__, &other.__shared__)
+            }
+        }
+
+        impl<T: Castable> Clone for $name<T> {
+            fn clone(&self) -> Self {
+                $name { __shared__: self.__shared__.clone(), offset: self.offset, p: PhantomData }
+            }
+        }
+
+        $(#[$rdoc])*
+        pub struct $read<'a, T: Castable> {
+            guard: $rguard<'a, $obj>,
+            offset: usize,
+            p: PhantomData<&'a T>
+        }
+
+        impl<'a, T: Castable> Deref for $read<'a, T> {
+            type Target = T;
+            fn deref(&self) -> &T {
+                let object = &*self.guard as *const $obj as *const u8;
+                unsafe { &*(object.add(self.offset) as *const T) }
+            }
+        }
+
+        unsafe impl<'a, T: Castable> Handle for $read<'a, T> {
+            fn object(&self) -> &dyn UnsafeCastable {
+                self.guard.as_dyn()
+            }
+        }
+
+        $(#[$wdoc])*
+        pub struct $write<'a, T: Castable> {
+            guard: $wguard<'a, $obj>,
+            offset: usize,
+            p: PhantomData<&'a mut T>
+        }
+
+        impl<'a, T: Castable> Deref for $write<'a, T> {
+            type Target = T;
+            fn deref(&self) -> &T {
+                let object = &*self.guard as *const $obj as *const u8;
+                unsafe { &*(object.add(self.offset) as *const T) }
+            }
+        }
+
+        impl<'a, T: Castable> DerefMut for $write<'a, T> {
+            fn deref_mut(&mut self) -> &mut T {
+                let object = &mut *self.guard as *mut $obj as *mut u8;
+                unsafe { &mut *(object.add(self.offset) as *mut T) }
+            }
+        }
+
+        unsafe impl<'a, T: Castable> Handle for $write<'a, T> {
+            fn object(&self) -> &dyn UnsafeCastable {
+                self.guard.as_dyn()
+            }
+        }
+
+        unsafe impl<'a, T: Castable> HandleMut for $write<'a, T> {
+            fn object_mut(&mut self) -> &mut dyn UnsafeCastable {
+                self.guard.as_dyn_mut()
+            }
+        }
+    };
+}
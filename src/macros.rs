@@ -8,22 +8,513 @@ macro_rules! upcast {
     }
 }
 
+/// Calls the implementation a virtual method overrides, from within an `override fn`.
+///
+/// `super_call!(self.income())` calls the nearest implementation of `income` above the
+/// overriding type, without dispatching on the bottom most sub-type again. Only methods of the
+/// trait the `override fn` belongs to are considered. The implementation is found at compile
+/// time, so it fails to compile if no super-type has a body for the method, e.g. if the only
+/// one above is a `virtual fn` without a body.
+///
+/// ```compile_fail
+/// # #[macro_use]
+/// # extern crate castable;
+/// inherit! {
+///     #[derive(Default)]
+///     abstract struct Employee;
+///     impl Income {
+///         virtual fn income(&self) -> f64;
+///     }
+///
+///     struct Salesperson: Employee;
+///     impl Income {
+///         override fn income(&self) -> f64 {
+///             // Employee has no implementation to call
+///             super_call!(self.income())
+///         }
+///     }
+/// }
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! super_call {
+    ($this:ident . $m:ident ( $($arg:expr),* $(,)* )) => {
+        {
+            #[allow(unused_imports)]
+            use $crate::{VirtualBody, VirtualBodyMut};
+            // autoderef finds the nearest super-type with a body for the method of the trait
+            // the override belongs to
+            let (body, this) = __castable_super::<{
+                $crate::virtual_key(__CASTABLE_TRAIT, stringify!($m))
+            }>($this).virtual_body();
+            body(this, $($arg),*)
+        }
+    };
+}
+
+/// Implements the castable traits for a struct whose super-type is stored in `$supf`.
+///
+/// `inherit!` calls this for you. It takes the same options, after the `;`:
+///
+///  - `abstract`, which must come first, leaves out `Constructable`; see `inherit!`.
+///  - `Debug { fields.. }` also implements `Debug`, with the listed fields flattened alongside
+///    those of the super-types.
+///  - `fields { fields.. }` only lists the fields, which the `Debug` of `Cast<T>` flattens
+///    if the struct has its own `Debug`, e.g. a derived one. `inherit!` always passes this.
+///  - `impl Trait { methods.. }` declares `Trait` with `virtual fn`s, or implements it with
+///    `override fn`s, see `inherit!`. It may be preceded by the visibility of the trait.
 #[macro_export]
 macro_rules! impl_inherit {
-    ($name:ident from $supf:ident : $sup:ty;) => {
-        impl $crate::UnsafeCastable for $name {
-            fn init_base(&mut self, s: Option<*mut $crate::UnsafeCastable>) {
+    ($name:ident from $supf:ident : $sup:ty; abstract @sealed $($opts:tt)*) => {
+        impl_inherit!{@opts [$name, $supf, $sup, abstract] [
+            fn is_abstract(&self) -> bool { true }
+        ] [] [
+            impl_inherit!{@inherits $name: $sup}
+        ] $($opts)*}
+    };
+    ($name:ident from $supf:ident : $sup:ty; abstract $($opts:tt)*) => {
+        impl_inherit!{@opts [$name, $supf, $sup, abstract] [
+            fn is_abstract(&self) -> bool { true }
+        ] [] [
+            impl_inherit!{@inheritable $name: $sup}
+        ] $($opts)*}
+    };
+    ($name:ident from $supf:ident : $sup:ty; @sealed $($opts:tt)*) => {
+        impl_inherit!{@opts [$name, $supf, $sup, concrete] [] [] [
+            impl $crate::Constructable for $name { type Super = $sup; }
+            impl_inherit!{@inherits $name: $sup}
+        ] $($opts)*}
+    };
+    ($name:ident from $supf:ident : $sup:ty; $($opts:tt)*) => {
+        impl_inherit!{@opts [$name, $supf, $sup, concrete] [] [] [
+            impl $crate::Constructable for $name { type Super = $sup; }
+            impl_inherit!{@inheritable $name: $sup}
+        ] $($opts)*}
+    };
+    // every struct checks that it may inherit from its super-type. Any struct may inherit from
+    // one outside a sealed block; `inherit!` implements `Inheritable` for the structs of a
+    // sealed block itself, so that only the other structs of the block may inherit from them
+    (@inherits $name:ident: $sup:ty) => {
+        const _: () = {
+            fn inherits<S: ?Sized + $crate::Inheritable<$name>>() {}
+            let _ = inherits::<$sup>;
+        };
+    };
+    (@inheritable $name:ident: $sup:ty) => {
+        impl<S: ?Sized> $crate::Inheritable<S> for $name {}
+        impl_inherit!{@inherits $name: $sup}
+    };
+    // options are collected as items for the UnsafeCastable impl, registrations of virtual
+    // method implementations, and extra items
+    (@opts [$name:ident, $supf:ident, $sup:ty, $kind:tt] [$($items:tt)*] $virtuals:tt
+        [$($extra:tt)*]
+        Debug { $($field:ident),* $(,)* } $($opts:tt)*
+    ) => {
+        impl_inherit!{@opts [$name, $supf, $sup, $kind]
+            [
+                $($items)*
+                fn debug_fields(&self, d: &mut ::std::fmt::DebugStruct) -> bool {
+                    let complete = $crate::UnsafeCastable::debug_fields(&self.$supf, d);
+                    $(d.field(stringify!($field), &self.$field);)*
+                    complete
+                }
+            ]
+            $virtuals
+            [
+                $($extra)*
+                impl ::std::fmt::Debug for $name {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                        $crate::UnsafeCastable::fmt_flat(self, f)
+                    }
+                }
+            ]
+            $($opts)*
+        }
+    };
+    (@opts [$name:ident, $supf:ident, $sup:ty, $kind:tt] [$($items:tt)*] $virtuals:tt
+        $extra:tt
+        fields { $($field:ident),* $(,)* } $($opts:tt)*
+    ) => {
+        impl_inherit!{@opts [$name, $supf, $sup, $kind]
+            [
+                $($items)*
+                fn debug_fields(&self, d: &mut ::std::fmt::DebugStruct) -> bool {
+                    // only one of them is used, depending on whether the layer is Debug
+                    #[allow(unused_imports)]
+                    use $crate::{DebugViaDebug, DebugViaNone};
+                    let complete = $crate::UnsafeCastable::debug_fields(&self.$supf, d);
+                    // a layer without its own Debug leaves its fields out
+                    if (&$crate::DebugWrap(self)).as_debug().is_none() {
+                        return false;
+                    }
+                    complete $(& match (&$crate::DebugWrap(&self.$field)).as_debug() {
+                        Some(v) => { d.field(stringify!($field), v); true }
+                        None => false
+                    })*
+                }
+            ]
+            $virtuals
+            $extra
+            $($opts)*
+        }
+    };
+    (@opts $t:tt $items:tt $virtuals:tt $extra:tt
+        $tvis:vis impl $tr:ident { $($methods:tt)* } $($opts:tt)*
+    ) => {
+        impl_inherit!{@methods $t [$tvis, $tr] $items $virtuals $extra { $($methods)* }
+            $($opts)*}
+    };
+    (@opts [$name:ident, $supf:ident, $sup:ty, $kind:tt] [$($items:tt)*] [] [$($extra:tt)*]) => {
+        impl_inherit!{@impl $name from $supf: $sup; { $($items)* }}
+        $($extra)*
+    };
+    (@opts [$name:ident, $supf:ident, $sup:ty, $kind:tt] [$($items:tt)*] [$($v:tt)*]
+        [$($extra:tt)*]
+    ) => {
+        impl_inherit!{@impl $name from $supf: $sup; {
+            $($items)*
+            #[allow(unused_variables)]
+            fn get_virtual(&self, name: &str) -> Option<&'static ::std::any::Any> {
+                $(impl_inherit!{@register $name, name, $v})*
+                None
+            }
+            #[allow(unused_variables)]
+            fn unresolved_virtual(&self, object: &$crate::UnsafeCastable) -> Option<&'static str> {
+                $(impl_inherit!{@unresolved object, $v})*
+                None
+            }
+        }}
+        $(impl_inherit!{@body $name, $v})*
+        $($extra)*
+    };
+    // a block of `virtual fn`s declares the trait, a block of `override fn`s implements one
+    // declared by a super-type
+    (@methods $t:tt $tr:tt $items:tt $virtuals:tt $extra:tt {
+        $(#[$attr:meta])* virtual $($methods:tt)*
+    } $($opts:tt)*) => {
+        impl_inherit!{@virtuals $t $tr $items $virtuals $extra [] [] {
+            $(#[$attr])* virtual $($methods)*
+        } $($opts)*}
+    };
+    (@methods $t:tt $tr:tt $items:tt $virtuals:tt $extra:tt {
+        $(#[$attr:meta])* override $($methods:tt)*
+    } $($opts:tt)*) => {
+        impl_inherit!{@overrides $t $tr $items $virtuals $extra {
+            $(#[$attr])* override $($methods)*
+        } $($opts)*}
+    };
+    (@methods $t:tt $tr:tt $items:tt $virtuals:tt $extra:tt {} $($opts:tt)*) => {
+        impl_inherit!{@opts $t $items $virtuals $extra $($opts)*}
+    };
+    // every `virtual fn` is declared in the trait, which is implemented for every handle to a
+    // sub-type by dispatching on the bottom most sub-type; a body is registered like an
+    // `override fn`
+    (@virtuals $t:tt [$tvis:vis, $tr:ident] $items:tt [$($virtuals:tt)*] $extra:tt
+        [$($decls:tt)*] [$($impls:tt)*] {
+        $(#[$attr:meta])* virtual fn $m:ident(&mut $s:ident $(, $a:ident : $ty:ty)* $(,)*)
+            $(-> $r:ty)* $body:block
+        $($methods:tt)*
+    } $($opts:tt)*) => {
+        impl_inherit!{@virtuals $t [$tvis, $tr] $items
+            [$($virtuals)* (virtual, $tr, mut $s, $m($($a: $ty),*) $(-> $r)*, $body)]
+            $extra
+            [$($decls)* impl_inherit!{@decl [$(#[$attr])*] mut $m($($a: $ty),*) $(-> $r)*}]
+            [$($impls)* impl_inherit!{@dispatch $tr, mut $m($($a: $ty),*) $(-> $r)*}]
+            { $($methods)* } $($opts)*
+        }
+    };
+    (@virtuals $t:tt [$tvis:vis, $tr:ident] $items:tt [$($virtuals:tt)*] $extra:tt
+        [$($decls:tt)*] [$($impls:tt)*] {
+        $(#[$attr:meta])* virtual fn $m:ident(&$s:ident $(, $a:ident : $ty:ty)* $(,)*)
+            $(-> $r:ty)* $body:block
+        $($methods:tt)*
+    } $($opts:tt)*) => {
+        impl_inherit!{@virtuals $t [$tvis, $tr] $items
+            [$($virtuals)* (virtual, $tr, $s, $m($($a: $ty),*) $(-> $r)*, $body)]
+            $extra
+            [$($decls)* impl_inherit!{@decl [$(#[$attr])*] $m($($a: $ty),*) $(-> $r)*}]
+            [$($impls)* impl_inherit!{@dispatch $tr, $m($($a: $ty),*) $(-> $r)*}]
+            { $($methods)* } $($opts)*
+        }
+    };
+    // a `virtual fn` without a body is abstract, and must be overridden
+    (@virtuals [$name:ident, $supf:ident, $sup:ty, abstract] [$tvis:vis, $tr:ident] $items:tt
+        [$($virtuals:tt)*] $extra:tt [$($decls:tt)*] [$($impls:tt)*] {
+        $(#[$attr:meta])* virtual fn $m:ident(&mut $s:ident $(, $a:ident : $ty:ty)* $(,)*)
+            $(-> $r:ty)*;
+        $($methods:tt)*
+    } $($opts:tt)*) => {
+        impl_inherit!{@virtuals [$name, $supf, $sup, abstract] [$tvis, $tr] $items
+            [$($virtuals)* (abstract, $tr, $m)]
+            $extra
+            [$($decls)* impl_inherit!{@decl [$(#[$attr])*] mut $m($($a: $ty),*) $(-> $r)*}]
+            [$($impls)* impl_inherit!{@dispatch $tr, mut $m($($a: $ty),*) $(-> $r)*}]
+            { $($methods)* } $($opts)*
+        }
+    };
+    (@virtuals [$name:ident, $supf:ident, $sup:ty, abstract] [$tvis:vis, $tr:ident] $items:tt
+        [$($virtuals:tt)*] $extra:tt [$($decls:tt)*] [$($impls:tt)*] {
+        $(#[$attr:meta])* virtual fn $m:ident(&$s:ident $(, $a:ident : $ty:ty)* $(,)*)
+            $(-> $r:ty)*;
+        $($methods:tt)*
+    } $($opts:tt)*) => {
+        impl_inherit!{@virtuals [$name, $supf, $sup, abstract] [$tvis, $tr] $items
+            [$($virtuals)* (abstract, $tr, $m)]
+            $extra
+            [$($decls)* impl_inherit!{@decl [$(#[$attr])*] $m($($a: $ty),*) $(-> $r)*}]
+            [$($impls)* impl_inherit!{@dispatch $tr, $m($($a: $ty),*) $(-> $r)*}]
+            { $($methods)* } $($opts)*
+        }
+    };
+    (@virtuals $t:tt $tr:tt $items:tt $virtuals:tt $extra:tt $decls:tt $impls:tt {
+        $(#[$attr:meta])* virtual fn $m:ident $args:tt $(-> $r:ty)*;
+        $($methods:tt)*
+    } $($opts:tt)*) => {
+        compile_error!(concat!(
+            "virtual fn ", stringify!($m), " needs a body outside an abstract struct"));
+    };
+    (@virtuals $t:tt $tr:tt $items:tt $virtuals:tt $extra:tt $decls:tt $impls:tt {
+        $(#[$attr:meta])* override fn $m:ident $($methods:tt)*
+    } $($opts:tt)*) => {
+        compile_error!(concat!("override fn ", stringify!($m),
+            " belongs in an impl block of its own, not one declaring virtual fns"));
+    };
+    (@virtuals [$name:ident, $supf:ident, $sup:ty, $kind:tt] [$tvis:vis, $tr:ident] $items:tt
+        $virtuals:tt [$($extra:tt)*] [$($decls:tt)*] [$($impls:tt)*] {} $($opts:tt)*
+    ) => {
+        impl_inherit!{@opts [$name, $supf, $sup, $kind] $items $virtuals
+            [
+                $($extra)*
+                #[allow(bare_trait_objects)]
+                $tvis trait $tr {
+                    $($decls)*
+                }
+
+                #[allow(bare_trait_objects)]
+                impl<H: ?Sized> $tr for H
+                    where H: $crate::Handle,
+                          <H as ::std::ops::Deref>::Target: $crate::SubtypeOf<$name>
+                {
+                    $($impls)*
+                }
+            ]
+            $($opts)*
+        }
+    };
+    (@overrides [$name:ident, $supf:ident, $sup:ty, $kind:tt] [$tvis:vis, $tr:ident] $items:tt
+        [$($virtuals:tt)*] [$($extra:tt)*] {
+        $(#[$attr:meta])* override fn $m:ident(&mut $s:ident $(, $a:ident : $ty:ty)* $(,)*)
+            $(-> $r:ty)* $body:block
+        $($methods:tt)*
+    } $($opts:tt)*) => {
+        impl_inherit!{@overrides [$name, $supf, $sup, $kind] [$tvis, $tr] $items
+            [$($virtuals)* (override, $tr, mut $s, $m($($a: $ty),*) $(-> $r)*, $body)]
+            [$($extra)* impl_inherit!{@check $tr, $name, mut $m($($ty),*) $(-> $r)*}]
+            { $($methods)* } $($opts)*
+        }
+    };
+    (@overrides [$name:ident, $supf:ident, $sup:ty, $kind:tt] [$tvis:vis, $tr:ident] $items:tt
+        [$($virtuals:tt)*] [$($extra:tt)*] {
+        $(#[$attr:meta])* override fn $m:ident(&$s:ident $(, $a:ident : $ty:ty)* $(,)*)
+            $(-> $r:ty)* $body:block
+        $($methods:tt)*
+    } $($opts:tt)*) => {
+        impl_inherit!{@overrides [$name, $supf, $sup, $kind] [$tvis, $tr] $items
+            [$($virtuals)* (override, $tr, $s, $m($($a: $ty),*) $(-> $r)*, $body)]
+            [$($extra)* impl_inherit!{@check $tr, $name, $m($($ty),*) $(-> $r)*}]
+            { $($methods)* } $($opts)*
+        }
+    };
+    (@overrides $t:tt $tr:tt $items:tt $virtuals:tt $extra:tt {
+        $(#[$attr:meta])* virtual fn $m:ident $($methods:tt)*
+    } $($opts:tt)*) => {
+        compile_error!(concat!("virtual fn ", stringify!($m),
+            " belongs in an impl block of its own, not one of override fns"));
+    };
+    (@overrides $t:tt $tr:tt $items:tt $virtuals:tt $extra:tt {} $($opts:tt)*) => {
+        impl_inherit!{@opts $t $items $virtuals $extra $($opts)*}
+    };
+    // an `override fn` must have the name and signature of a `virtual fn` of the trait, which
+    // a handle to the struct implements
+    (@check $tr:ident, $name:ident, mut $m:ident($($ty:ty),*) $(-> $r:ty)*) => {
+        #[allow(bare_trait_objects)]
+        const _: fn(&mut $crate::Cast<$name>, $($ty),*) $(-> $r)* =
+            <$crate::Cast<$name> as $tr>::$m;
+    };
+    (@check $tr:ident, $name:ident, $m:ident($($ty:ty),*) $(-> $r:ty)*) => {
+        #[allow(bare_trait_objects)]
+        const _: fn(&$crate::Cast<$name>, $($ty),*) $(-> $r)* = <$crate::Cast<$name> as $tr>::$m;
+    };
+    (@decl [$(#[$attr:meta])*] mut $m:ident($($a:ident : $ty:ty),*) $(-> $r:ty)*) => {
+        $(#[$attr])*
+        fn $m(&mut self, $($a: $ty),*) $(-> $r)* where Self: $crate::HandleMut;
+    };
+    (@decl [$(#[$attr:meta])*] $m:ident($($a:ident : $ty:ty),*) $(-> $r:ty)*) => {
+        $(#[$attr])*
+        fn $m(&self, $($a: $ty),*) $(-> $r)*;
+    };
+    // the dispatching method looks up the most derived implementation, starting from the
+    // bottom most sub-type of the object the handle holds
+    (@dispatch $tr:ident, mut $m:ident($($a:ident : $ty:ty),*) $(-> $r:ty)*) => {
+        fn $m(&mut self, $($a: $ty),*) $(-> $r)* where Self: $crate::HandleMut {
+            let f = $crate::find_virtual::<
+                unsafe fn(&mut $crate::UnsafeCastable, $($ty),*) $(-> $r)*
+            >($crate::Handle::object(self), impl_inherit!(@key $tr, $m));
+            // the handle holds the whole object
+            unsafe { f($crate::HandleMut::object_mut(self), $($a),*) }
+        }
+    };
+    (@dispatch $tr:ident, $m:ident($($a:ident : $ty:ty),*) $(-> $r:ty)*) => {
+        fn $m(&self, $($a: $ty),*) $(-> $r)* {
+            let f = $crate::find_virtual::<
+                unsafe fn(&$crate::UnsafeCastable, $($ty),*) $(-> $r)*
+            >($crate::Handle::object(self), impl_inherit!(@key $tr, $m));
+            unsafe { f($crate::Handle::object(self), $($a),*) }
+        }
+    };
+    // implementations are registered under the trait's name as well as the method's, so that
+    // methods of the same name in different traits don't collide
+    (@key $tr:ident, $m:ident) => { concat!(stringify!($tr), "::", stringify!($m)) };
+    (@hash $tr:ident, $m:ident) => { $crate::virtual_key(stringify!($tr), stringify!($m)) };
+    // an abstract `virtual fn` has nothing to register, but the object must implement it
+    (@register $name:ident, $n:ident, (abstract, $tr:ident, $m:ident)) => {};
+    (@body $name:ident, (abstract, $tr:ident, $m:ident)) => {};
+    (@unresolved $object:ident, (abstract, $tr:ident, $m:ident)) => {
+        if !$crate::has_virtual($object, impl_inherit!(@key $tr, $m)) {
+            return Some(impl_inherit!(@key $tr, $m));
+        }
+    };
+    (@unresolved $object:ident, $v:tt) => {};
+    // the registered function is unsafe, as it takes the whole object without checking
+    (@register $name:ident, $n:ident,
+        ($kind:tt, $tr:ident, mut $s:ident, $m:ident($($a:ident : $ty:ty),*) $(-> $r:ty)*,
+            $body:block)
+    ) => {
+        if $n == impl_inherit!(@key $tr, $m) {
+            static BODY: unsafe fn(&mut $crate::UnsafeCastable, $($ty),*) $(-> $r)* = {
+                unsafe fn call(object: &mut $crate::UnsafeCastable, $($a: $ty),*) $(-> $r)* {
+                    let layer = $crate::SuperLayer::<$name, { impl_inherit!(@hash $tr, $m) }>
+                        ::of_mut(object);
+                    let (body, this) = $crate::VirtualBodyMut::virtual_body(layer);
+                    body(this, $($a),*)
+                }
+                call
+            };
+            return Some(&BODY);
+        }
+    };
+    (@register $name:ident, $n:ident,
+        ($kind:tt, $tr:ident, $s:ident, $m:ident($($a:ident : $ty:ty),*) $(-> $r:ty)*,
+            $body:block)
+    ) => {
+        if $n == impl_inherit!(@key $tr, $m) {
+            static BODY: unsafe fn(&$crate::UnsafeCastable, $($ty),*) $(-> $r)* = {
+                unsafe fn call(object: &$crate::UnsafeCastable, $($a: $ty),*) $(-> $r)* {
+                    let layer = $crate::SuperLayer::<$name, { impl_inherit!(@hash $tr, $m) }>
+                        ::of(object);
+                    let (body, this) = $crate::VirtualBody::virtual_body(layer);
+                    body(this, $($a),*)
+                }
+                call
+            };
+            return Some(&BODY);
+        }
+    };
+    // an implementation's body sees the whole object as a `VirtualSelf<$name>`. The
+    // `SuperLayer` of `$name` keyed on the method hands it out, which `super_call!` in the body
+    // of a sub-type finds through autoderef, starting from its super-type
+    (@body $name:ident,
+        ($kind:tt, $tr:ident, mut $s:ident, $m:ident($($a:ident : $ty:ty),*) $(-> $r:ty)*,
+            $body:block)
+    ) => {
+        #[allow(bare_trait_objects)]
+        const _: () = {
+            trait Body { fn body(&mut $s, $($a: $ty),*) $(-> $r)*; }
+            impl Body for $crate::VirtualSelf<$name> {
+                fn body(&mut $s, $($a: $ty),*) $(-> $r)* $body
+            }
+            impl $crate::VirtualBodyMut<$name>
+                for $crate::SuperLayer<$name, { impl_inherit!(@hash $tr, $m) }>
+            {
+                type Body = fn(&mut $crate::VirtualSelf<$name>, $($ty),*) $(-> $r)*;
+                fn virtual_body(&mut self) -> (Self::Body, &mut $crate::VirtualSelf<$name>) {
+                    (<$crate::VirtualSelf<$name> as Body>::body, self.body_self_mut())
+                }
+            }
+            // found by `super_call!` in the body
+            #[allow(dead_code)]
+            const __CASTABLE_TRAIT: &str = stringify!($tr);
+            #[allow(dead_code)]
+            fn __castable_super<const KEY: u64>(this: &mut $crate::VirtualSelf<$name>)
+                -> &mut $crate::SuperLayer<<$name as ::std::ops::Deref>::Target, KEY>
+            {
+                $crate::SuperLayer::above_mut(this)
+            }
+        };
+    };
+    (@body $name:ident,
+        ($kind:tt, $tr:ident, $s:ident, $m:ident($($a:ident : $ty:ty),*) $(-> $r:ty)*,
+            $body:block)
+    ) => {
+        #[allow(bare_trait_objects)]
+        const _: () = {
+            trait Body { fn body(&$s, $($a: $ty),*) $(-> $r)*; }
+            impl Body for $crate::VirtualSelf<$name> {
+                fn body(&$s, $($a: $ty),*) $(-> $r)* $body
+            }
+            impl $crate::VirtualBody<$name>
+                for $crate::SuperLayer<$name, { impl_inherit!(@hash $tr, $m) }>
+            {
+                type Body = fn(&$crate::VirtualSelf<$name>, $($ty),*) $(-> $r)*;
+                fn virtual_body(&self) -> (Self::Body, &$crate::VirtualSelf<$name>) {
+                    (<$crate::VirtualSelf<$name> as Body>::body, self.body_self())
+                }
+            }
+            #[allow(dead_code)]
+            const __CASTABLE_TRAIT: &str = stringify!($tr);
+            #[allow(dead_code)]
+            fn __castable_super<const KEY: u64>(this: &$crate::VirtualSelf<$name>)
+                -> &$crate::SuperLayer<<$name as ::std::ops::Deref>::Target, KEY>
+            {
+                $crate::SuperLayer::above(this)
+            }
+        };
+    };
+    (@impl $name:ident from $supf:ident : $sup:ty; { $($items:tt)* }) => {
+        #[allow(bare_trait_objects)]
+        unsafe impl $crate::UnsafeCastable for $name {
+            unsafe fn init_base(&mut self, s: Option<$crate::Instance>) {
                 self.$supf.init_base(s);
             }
             fn ident() -> ::std::any::TypeId { ::std::any::TypeId::of::<$name>() }
+            fn depth() -> usize { <$sup as $crate::UnsafeCastable>::depth() + 1 }
+            fn type_name() -> &'static str { stringify!($name) }
+            fn hierarchy() -> Vec<(::std::any::TypeId, &'static str)> {
+                let mut h = vec![(Self::ident(), Self::type_name())];
+                h.extend(<$sup as $crate::UnsafeCastable>::hierarchy());
+                h
+            }
             fn get_ident(&self) -> ::std::any::TypeId { Self::ident() }
+            fn get_type_name(&self) -> &'static str { Self::type_name() }
             fn get_super(&self) -> &$crate::UnsafeCastable { &self.$supf }
             fn get_super_mut(&mut self) -> &mut $crate::UnsafeCastable { &mut self.$supf }
+            fn get_base(&self) -> &$crate::Base { $crate::UnsafeCastable::get_base(&self.$supf) }
+            fn get_base_mut(&mut self) -> &mut $crate::Base {
+                $crate::UnsafeCastable::get_base_mut(&mut self.$supf)
+            }
+            fn clone_dyn(&self) -> Option<$crate::Cast<$crate::Base>> {
+                #[allow(unused_imports)]
+                use $crate::{CloneViaClone, CloneViaNone};
+                (&$crate::CloneWrap(self)).clone_cast()
+            }
             fn as_any(&self) -> &::std::any::Any { self }
             fn as_any_mut(&mut self) -> &mut ::std::any::Any { self }
+            fn as_dyn(&self) -> &$crate::UnsafeCastable { self }
+            fn as_dyn_mut(&mut self) -> &mut $crate::UnsafeCastable { self }
+            $($items)*
         }
 
-        impl $crate::Constructable for $name { type Super = $sup; }
+        unsafe impl<S> $crate::SubtypeOf<S> for $name where $sup: $crate::SubtypeOf<S> {}
 
         impl $crate::Castable for $name {}
 
@@ -40,123 +531,482 @@ macro_rules! impl_inherit {
 
 // big thanks to https://danielkeep.github.io/tlborm/book/blk-enum-parsing.html
 // and to Quxxy from #rust
+//
+// `#[inherit(..)]` attributes are collected as options rather than emitted. The only option is
+// `Debug`, which implements a `Debug` that flattens `__super__` instead of nesting it.
+/// Declares structs inheriting from `Base`, or from each other.
+///
+/// A struct may be followed by `impl Trait { .. }` blocks of methods that dispatch on the
+/// bottom most sub-type of the object. A block of `virtual fn`s declares `Trait`, as visible as
+/// the struct, and implements it for every `Handle` to the struct or its sub-types, e.g. `Cast`
+/// or a `CastCell` guard. A block of `override fn`s naming the same trait replaces the
+/// implementations for a sub-type. Calling a method through any handle calls the nearest
+/// implementation to the bottom most sub-type.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate castable;
+/// # use castable::Cast;
+/// inherit! {
+///     pub struct Employee {
+///         pub hours: f64,
+///         pub pay: f64
+///     }
+///     impl Income {
+///         virtual fn income(&self) -> f64 {
+///             self.hours * self.pay
+///         }
+///     }
+///
+///     pub struct Salesperson: Employee {
+///         pub sales: u32
+///     }
+///     impl Income {
+///         override fn income(&self) -> f64 {
+///             // calls Employee's implementation
+///             super_call!(self.income()) + 10.0 * self.sales as f64
+///         }
+///     }
+/// }
+///
+/// # fn main() {
+/// let e: Cast<Employee> = construct!( Salesperson as Employee {
+///     sales: 2,
+///     sup.. Employee {
+///         hours: 10.0,
+///         pay: 15.0
+///     }
+/// });
+/// assert_eq!(e.income(), 170.0);
+/// # }
+/// ```
+///
+/// Methods take `&self` or `&mut self`, and their arguments must be plain identifiers. An
+/// `override fn` must have the same name and signature as a `virtual fn` of the trait, which is
+/// checked at compile time. In a body, `self` is a `VirtualSelf<Self>`, which derefs to the
+/// struct and is itself a handle, so the virtual methods it calls dispatch on the bottom most
+/// sub-type too.
+///
+/// ```compile_fail
+/// # #[macro_use]
+/// # extern crate castable;
+/// inherit! {
+///     struct Employee { hours: f64 }
+///     impl Income {
+///         virtual fn income(&self) -> f64 { self.hours }
+///     }
+///
+///     struct Salesperson: Employee { sales: u32 }
+///     impl Income {
+///         // Income has no virtual fn incme
+///         override fn incme(&self) -> f64 { 0.0 }
+///     }
+/// }
+/// # fn main() {}
+/// ```
+///
+/// An `abstract struct` can only be the super-type of another struct: it doesn't implement
+/// `Constructable`, so it can't be `init()`ed, or be the outermost type in `construct!`, raw or
+/// not. A value of it made some other way, e.g. by `Default`, makes `Cast::new` panic.
+///
+/// Its `virtual fn`s may omit their body. This isn't checked at compile time: a sub-type that
+/// doesn't override one compiles, but initializing an object of it panics, with
+/// `Constructable::init` or `Cast::new`.
+///
+/// ```compile_fail
+/// # #[macro_use]
+/// # extern crate castable;
+/// inherit! {
+///     #[derive(Default)]
+///     abstract struct Shape;
+///     impl Area {
+///         virtual fn area(&self) -> f64;
+///     }
+/// }
+///
+/// # fn main() {
+/// let shape = construct!( raw Shape {} );
+/// # }
+/// ```
+///
+/// Starting a block with `sealed Root as Kind, KindMut;` generates two enums with a variant
+/// for each struct in the block that isn't abstract, and implements `Sealed` for each struct,
+/// so `Handle::kind()` and `HandleMut::kind_mut()` can match the bottom most sub-type
+/// exhaustively. Every struct in the block must descend from `Root`, and no struct outside the
+/// block can inherit from one inside it, whether declared with `inherit!` or `impl_inherit!`.
+///
+/// The enums are as visible as the block, which must be at least as visible as its structs:
+/// a block of `pub struct`s starts with `pub sealed`, or the enums are private types in the
+/// public interface of `Sealed`.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate castable;
+/// # use castable::{Cast, Handle};
+/// inherit! {
+///     sealed Shape as ShapeKind, ShapeKindMut;
+///
+///     #[derive(Default)]
+///     abstract struct Shape;
+///     struct Circle: Shape { radius: f64 }
+///     struct Square: Shape { side: f64 }
+/// }
+///
+/// fn area(shape: &Cast<Shape>) -> f64 {
+///     match shape.kind() {
+///         ShapeKind::Circle(c) => 3.0 * c.radius * c.radius,
+///         ShapeKind::Square(s) => s.side * s.side
+///     }
+/// }
+///
+/// # fn main() {
+/// let shape: Cast<Shape> = construct!( Square as Shape { side: 2.0 } );
+/// assert_eq!(area(&shape), 4.0);
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// # #[macro_use]
+/// # extern crate castable;
+/// inherit! {
+///     sealed Shape as ShapeKind, ShapeKindMut;
+///
+///     #[derive(Default)]
+///     abstract struct Shape;
+///     #[derive(Default)]
+///     struct Circle: Shape { radius: f64 }
+/// }
+///
+/// inherit! {
+///     // ShapeKind has no variant for it
+///     struct Ellipse: Circle { ratio: f64 }
+/// }
+/// # fn main() {}
+/// ```
 #[macro_export]
 macro_rules! inherit {
+    // sealed hierarchy: collect the structs of the block, then parse it as usual, marking each
+    // struct as part of the block
+    ($vis:vis sealed $root:ident as $kind:ident, $kind_mut:ident; $($tail:tt)*) => {
+        inherit!{ @sealed $root [$vis $root $kind $kind_mut] [] [] [] $($tail)* }
+    };
+    (@sealed $root:ident $s:tt $c:tt [ $($all:ident)* ] [ $($out:tt)* ]
+        pub abstract struct $name:ident $($tail:tt)*
+    ) => {
+        inherit!{ @sealed $root $s $c [ $($all)* $name ]
+            [ $($out)* #[inherit(@sealed $root)] pub abstract struct $name ] $($tail)* }
+    };
+    (@sealed $root:ident $s:tt $c:tt [ $($all:ident)* ] [ $($out:tt)* ]
+        abstract struct $name:ident $($tail:tt)*
+    ) => {
+        inherit!{ @sealed $root $s $c [ $($all)* $name ]
+            [ $($out)* #[inherit(@sealed $root)] abstract struct $name ] $($tail)* }
+    };
+    (@sealed $root:ident $s:tt [ $($c:ident)* ] [ $($all:ident)* ] [ $($out:tt)* ]
+        pub struct $name:ident $($tail:tt)*
+    ) => {
+        inherit!{ @sealed $root $s [ $($c)* $name ] [ $($all)* $name ]
+            [ $($out)* #[inherit(@sealed $root)] pub struct $name ] $($tail)* }
+    };
+    (@sealed $root:ident $s:tt [ $($c:ident)* ] [ $($all:ident)* ] [ $($out:tt)* ]
+        struct $name:ident $($tail:tt)*
+    ) => {
+        inherit!{ @sealed $root $s [ $($c)* $name ] [ $($all)* $name ]
+            [ $($out)* #[inherit(@sealed $root)] struct $name ] $($tail)* }
+    };
+    (@sealed $root:ident $s:tt $c:tt $all:tt [ $($out:tt)* ] $skip:tt $($tail:tt)*) => {
+        inherit!{ @sealed $root $s $c $all [ $($out)* $skip ] $($tail)* }
+    };
+    (@sealed $r:ident [$vis:vis $root:ident $kind:ident $kind_mut:ident] [ $($c:ident)* ]
+        [ $($all:ident)* ] [ $($out:tt)* ]
+    ) => {
+        /// A reference to an object of the sealed hierarchy, as its bottom most sub-type.
+        #[derive(Clone, Copy)]
+        $vis enum $kind<'a> {
+            $($c(&'a $c),)*
+        }
+
+        /// A mutable reference to an object of the sealed hierarchy, as its bottom most
+        /// sub-type.
+        $vis enum $kind_mut<'a> {
+            $($c(&'a mut $c),)*
+        }
+
+        // the bottom most sub-type of an object of the block is always one of its concrete
+        // types, as nothing outside the block can inherit from it
+        #[allow(bare_trait_objects)]
+        impl<'a> $kind<'a> {
+            fn of(object: &'a $crate::UnsafeCastable) -> Self {
+                $(
+                    if object.get_ident() == ::std::any::TypeId::of::<$c>() {
+                        return $kind::$c(object.as_any().downcast_ref::<$c>()
+                            .expect("found by its TypeId"));
+                    }
+                )*
+                unreachable!("{} is not in the sealed block of {}", object.get_type_name(),
+                    stringify!($root))
+            }
+        }
+
+        #[allow(bare_trait_objects)]
+        impl<'a> $kind_mut<'a> {
+            fn of(object: &'a mut $crate::UnsafeCastable) -> Self {
+                $(
+                    if object.get_ident() == ::std::any::TypeId::of::<$c>() {
+                        return $kind_mut::$c(object.as_any_mut().downcast_mut::<$c>()
+                            .expect("found by its TypeId"));
+                    }
+                )*
+                unreachable!("{} is not in the sealed block of {}", object.get_type_name(),
+                    stringify!($root))
+            }
+        }
+
+        // only the structs of the block may inherit from one another, as nothing outside can
+        // name `Member`
+        #[allow(private_bounds)]
+        const _: () = {
+            trait Member {}
+            $(
+                impl Member for $all {}
+                impl<S: ?Sized + Member> $crate::Inheritable<S> for $all {}
+            )*
+        };
+
+        $(
+            // every struct of the block descends from the root, so it has a variant
+            #[allow(bare_trait_objects)]
+            impl $crate::Sealed for $all where $all: $crate::SubtypeOf<$root> {
+                type Kind<'a> = $kind<'a>;
+                type KindMut<'a> = $kind_mut<'a>;
+
+                fn kind_of(object: &$crate::UnsafeCastable) -> $kind<'_> {
+                    $kind::of(object)
+                }
+
+                fn kind_of_mut(object: &mut $crate::UnsafeCastable) -> $kind_mut<'_> {
+                    $kind_mut::of(object)
+                }
+            }
+        )*
+
+        inherit!{ $($out)* }
+    };
+    // phase 1: a struct of a sealed block
+    (#[inherit(@sealed $root:ident)] $($tail:tt)*) => {
+        inherit!{ meta [] opts [sealed($root)] $($tail)* }
+    };
     // phase 1: public struct parse
     (pub struct $($tail:tt)*) => {
-        inherit!{ meta [] @pub struct $($tail)* }
+        inherit!{ meta [] opts [] @pub struct $($tail)* }
     };
     // phase 1: private struct parse
     (struct $($tail:tt)*) => {
-        inherit!{ meta [] @priv struct $($tail)* }
+        inherit!{ meta [] opts [] @priv struct $($tail)* }
+    };
+    // phase 1: abstract struct parse
+    (pub abstract struct $($tail:tt)*) => {
+        inherit!{ meta [] opts [abstract] @pub struct $($tail)* }
+    };
+    (abstract struct $($tail:tt)*) => {
+        inherit!{ meta [] opts [abstract] @priv struct $($tail)* }
+    };
+    (#[inherit($($opt:ident),* $(,)*)] $($tail:tt)*) => {
+        inherit!{ meta [] opts [$($opt)*] $($tail)* }
     };
     (#[$nm:meta] $($tail:tt)*) => {
-        inherit!{ meta [$nm,] $($tail)* }
+        inherit!{ meta [$nm,] opts [] $($tail)* }
+    };
+    // phase 0: handle the mark of a struct of a sealed block
+    (meta $meta:tt opts [ $($opts:tt)* ] #[inherit(@sealed $root:ident)] $($tail:tt)*) => {
+        inherit!{ meta $meta opts [$($opts)* sealed($root)] $($tail)* }
+    };
+    // phase 0: handle options
+    (meta $meta:tt opts [ $($opts:tt)* ] #[inherit($($opt:ident),* $(,)*)] $($tail:tt)*) => {
+        inherit!{ meta $meta opts [$($opts)* $($opt)*] $($tail)* }
     };
     // phase 0: handle attributes
-    (meta [ $($meta:tt)* ] #[$nm:meta] $($tail:tt)*) => {
-        inherit!{ meta [$($meta)* $nm,] $($tail)* }
+    (meta [ $($meta:tt)* ] opts $opts:tt #[$nm:meta] $($tail:tt)*) => {
+        inherit!{ meta [$($meta)* $nm,] opts $opts $($tail)* }
+    };
+    // phase 1: abstract struct parse
+    (meta $meta:tt opts [ $($opts:tt)* ] pub abstract struct $($tail:tt)*) => {
+        inherit!{ meta $meta opts [$($opts)* abstract] @pub struct $($tail)* }
+    };
+    (meta $meta:tt opts [ $($opts:tt)* ] abstract struct $($tail:tt)*) => {
+        inherit!{ meta $meta opts [$($opts)* abstract] @priv struct $($tail)* }
     };
     // phase 1: public struct parse
-    (meta $meta:tt pub struct $($tail:tt)*) => {
-        inherit!{ meta $meta @pub struct $($tail)* }
+    (meta $meta:tt opts $opts:tt pub struct $($tail:tt)*) => {
+        inherit!{ meta $meta opts $opts @pub struct $($tail)* }
     };
     // phase 1: private struct parse
-    (meta $meta:tt struct $($tail:tt)*) => {
-        inherit!{ meta $meta @priv struct $($tail)* }
+    (meta $meta:tt opts $opts:tt struct $($tail:tt)*) => {
+        inherit!{ meta $meta opts $opts @priv struct $($tail)* }
     };
     // phase 2: non-empty struct parse
-    (meta $meta:tt @$v:ident struct $name:ident: $sup:ty { $($queue:tt)* } $($tail:tt)*) => {
-        inherit!{meta $meta @impl @struct [@$v, $name, $sup]
+    (meta $meta:tt opts $opts:tt @$v:ident struct $name:ident: $sup:ty { $($queue:tt)* }
+        $($tail:tt)*
+    ) => {
+        inherit!{meta $meta opts $opts @impl @struct [@$v, $name, $sup]
             @queue [ $($queue)* , ]
+            @impls []
             $($tail)*
         }
     };
     // phase 2: empty struct parse
-    (meta $meta:tt @$v:ident struct $name:ident: $sup:ty; $($tail:tt)*) => {
-        inherit!{meta $meta @impl @struct [@$v, $name, $sup]
+    (meta $meta:tt opts $opts:tt @$v:ident struct $name:ident: $sup:ty; $($tail:tt)*) => {
+        inherit!{meta $meta opts $opts @impl @struct [@$v, $name, $sup]
             @queue []
+            @impls []
             $($tail)*
         }
     };
     // phase 2: non-empty struct, default $sup
-    (meta $meta:tt @$v:ident struct $name:ident { $($queue:tt)* } $($tail:tt)*) => {
-        inherit!{meta $meta @impl @struct [@$v, $name, $crate::Base]
+    (meta $meta:tt opts $opts:tt @$v:ident struct $name:ident { $($queue:tt)* } $($tail:tt)*) => {
+        inherit!{meta $meta opts $opts @impl @struct [@$v, $name, $crate::Base]
             @queue [ $($queue)* , ]
+            @impls []
             $($tail)*
         }
     };
     // phase 2: empty struct, default $sup
-    (meta $meta:tt @$v:ident struct $name:ident; $($tail:tt)*) => {
-        inherit!{meta $meta @impl @struct [@$v, $name, $crate::Base]
+    (meta $meta:tt opts $opts:tt @$v:ident struct $name:ident; $($tail:tt)*) => {
+        inherit!{meta $meta opts $opts @impl @struct [@$v, $name, $crate::Base]
             @queue []
+            @impls []
+            $($tail)*
+        }
+    };
+    // phase 3: collect the impl blocks of virtual and override fns following the struct
+    (meta $meta:tt opts $opts:tt @impl @struct $s:tt
+        @queue $queue:tt
+        @impls [ $($impls:tt)* ]
+        impl $tr:ident { $($methods:tt)* }
+        $($tail:tt)*
+    ) => {
+        inherit!{meta $meta opts $opts @impl @struct $s
+            @queue $queue
+            @impls [ $($impls)* ($tr { $($methods)* }) ]
             $($tail)*
         }
     };
-    // phase 3: impl traits for struct
-    (meta $meta:tt @impl @struct [@$v:ident, $name:ident, $sup:ty]
+    (meta $meta:tt opts $opts:tt @impl @struct $s:tt
         @queue $queue:tt
+        @impls $impls:tt
+        impl { $($methods:tt)* }
         $($tail:tt)*
     ) => {
-        inherit!{meta $meta @struct [@$v, $name, $sup]
+        compile_error!("an impl block in inherit! names the trait it declares or overrides, \
+            e.g. `impl Income { .. }`");
+    };
+    // phase 3: generate the struct and its methods, then the rest of the input
+    (meta $meta:tt opts $opts:tt @impl @struct [@$v:ident, $name:ident, $sup:ty]
+        @queue $queue:tt
+        @impls $impls:tt
+        $($tail:tt)*
+    ) => {
+        inherit!{meta $meta opts $opts @struct [@$v, $name, $sup, $impls]
             @queue $queue
             @pub []
             @priv []
         }
 
-        impl_inherit!{$name from __super__: $sup;}
-
         inherit!{ $($tail)* }
     };
     // phase 4: public fields
-    (meta $meta:tt @struct $m:tt
+    (meta $meta:tt opts $opts:tt @struct $m:tt
         @queue [ pub  $a:ident : $b:ty, $($tail:tt)* ]
         @pub [ $($public:tt)* ]
         @priv $private:tt
     ) => {
-        inherit!{meta $meta @struct $m
+        inherit!{meta $meta opts $opts @struct $m
             @queue [ $($tail)* ]
             @pub [ $($public)* pub $a : $b, ]
             @priv $private
         }
     };
     // phase 4: private fields
-    (meta $meta:tt @struct $m:tt
+    (meta $meta:tt opts $opts:tt @struct $m:tt
         @queue [ $a:ident : $b:ty, $($tail:tt)* ]
         @pub $public:tt
         @priv [ $($private:tt)* ]
     ) => {
-        inherit!{meta $meta @struct $m
+        inherit!{meta $meta opts $opts @struct $m
             @queue [ $($tail)* ]
             @pub $public
             @priv [ $($private)* $a : $b, ]
         }
     };
     // phase 5: public struct generation
-    (meta [$($meta:meta),* $(,)*] @struct [@pub, $name:ident, $sup:ty]
+    (meta [$($meta:meta),* $(,)*] opts $opts:tt @struct [@pub, $name:ident, $sup:ty, $methods:tt]
         @queue [ $(,)* ]
         @pub  [ $(pub  $a:ident : $b:ty,)* ]
         @priv [ $( $c:ident : $d:ty,)* ]
     ) => {
         $(#[$meta])*
+        #[repr(C)]
         pub struct $name {
             pub __super__: $sup,
             $(pub  $a : $b,)*
             $(     $c : $d,)*
         }
+
+        inherit!{ @opts $opts $name: $sup [ $($a)* $($c)* ] [pub] $methods }
     };
     // phase 5: private struct generation
-    (meta [$($meta:meta),* $(,)*] @struct [@priv, $name:ident, $sup:ty]
+    (meta [$($meta:meta),* $(,)*] opts $opts:tt @struct [@priv, $name:ident, $sup:ty, $methods:tt]
         @queue [ $(,)* ]
         @pub  [ $(pub  $a:ident : $b:ty,)* ]
         @priv [ $( $c:ident : $d:ty,)* ]
     ) => {
         $(#[$meta])*
+        #[repr(C)]
         struct $name {
             __super__: $sup,
             $(pub  $a : $b,)*
             $(     $c : $d,)*
         }
+
+        inherit!{ @opts $opts $name: $sup [ $($a)* $($c)* ] [] $methods }
+    };
+    // phase 6: translate options for impl_inherit!, which wants `abstract` first, then the
+    // sealed block the struct belongs to. The fields are always passed on, so layers that only
+    // derive Debug are still flattened
+    (@opts $opts:tt $name:ident: $sup:ty [ $($f:ident)* ] $vis:tt $methods:tt) => {
+        inherit!{ @options $opts [] [fields { $($f),* }] $name: $sup [ $($f)* ] $vis $methods }
+    };
+    (@options [Debug $($opts:tt)*] $acc:tt $fields:tt $name:ident: $sup:ty [ $($f:ident)* ]
+        $vis:tt $methods:tt
+    ) => {
+        inherit!{ @options [$($opts)*] $acc [Debug { $($f),* }] $name: $sup [ $($f)* ]
+            $vis $methods
+        }
+    };
+    (@options [abstract $($opts:tt)*] [ $($acc:tt)* ] $fields:tt $name:ident: $sup:ty
+        [ $($f:ident)* ] $vis:tt $methods:tt
+    ) => {
+        inherit!{ @options [$($opts)*] [abstract $($acc)*] $fields $name: $sup [ $($f)* ]
+            $vis $methods
+        }
+    };
+    (@options [sealed($root:ident) $($opts:tt)*] [ $($acc:tt)* ] $fields:tt $name:ident: $sup:ty
+        [ $($f:ident)* ] $vis:tt $methods:tt
+    ) => {
+        inherit!{ @options [$($opts)*] [$($acc)* @sealed] $fields $name: $sup [ $($f)* ]
+            $vis $methods
+        }
+    };
+    // phase 7: impl traits for struct; the traits of its virtual fns are as visible as it is
+    (@options [] [ $($acc:tt)* ] [ $($fields:tt)* ] $name:ident: $sup:ty [ $($f:ident)* ]
+        [pub] [ $( ($tr:ident $methods:tt) )* ]
+    ) => {
+        impl_inherit!{$name from __super__: $sup; $($acc)* $($fields)* $(pub impl $tr $methods)*}
+    };
+    (@options [] [ $($acc:tt)* ] [ $($fields:tt)* ] $name:ident: $sup:ty [ $($f:ident)* ]
+        [] [ $( ($tr:ident $methods:tt) )* ]
+    ) => {
+        impl_inherit!{$name from __super__: $sup; $($acc)* $($fields)* $(impl $tr $methods)*}
     };
     // base case of recursion
     () => {};
@@ -164,8 +1014,12 @@ macro_rules! inherit {
 
 #[macro_export]
 macro_rules! construct {
-    // phase 1: struct expr recognition
+    // phase 1: struct expr recognition; only a Constructable type can be raw on its own
     (raw $t:ident { $($tail:tt)* }) => {
+        $crate::raw::<$t>(construct!( parse [] $t { $($tail)* } ))
+    };
+    // phase 1: struct expr recognition of a super-type, which may be abstract
+    (layer $t:ident { $($tail:tt)* }) => {
         construct!( parse [] $t { $($tail)* } )
     };
     // phase 1: struct expr recognition and init call
@@ -175,7 +1029,7 @@ macro_rules! construct {
     // phase 1: struct expr recognition and init call
     ($t:ident as $s:ident { $($tail:tt)* }) => {
         <$t as $crate::Constructable>::init(
-            construct!( parse [] $t { $($tail)* } )).cast_as::<$s>()
+            construct!( parse [] $t { $($tail)* } )).upcast::<$s>()
     };
     // phase 2: parse normal `field: value` part
     (parse [ $($f:tt)* ] $t:ident { $a:ident: $b:expr, $($tail:tt)* }) => {
@@ -187,12 +1041,13 @@ macro_rules! construct {
     };
     // phase 2: prepare super struct, prepare for expression output
     (parse [ $($f:tt)* ] $t:ident { sup.. $($tail:tt)* }) => {
-        construct!( expr [ $($f)* __super__ : construct!( raw $($tail)* ), ] $t )
+        construct!( expr [ $($f)* __super__ : construct!( layer $($tail)* ), ] $t )
     };
-    // phase 2: prepare super struct, prepare for expression output
+    // phase 2: prepare super struct, prepare for expression output; the super-type is found
+    // through Deref, as abstract types don't implement Constructable
     (parse [ $($f:tt)* ] $t:ident { $(,)* }) => {
         construct!( expr [ $($f)* __super__ :
-            <<$t as $crate::Constructable>::Super as Default>::default(), ] $t )
+            <<$t as ::std::ops::Deref>::Target as Default>::default(), ] $t )
     };
     // phase 3: output modified strut expression
     (expr [ $($a:ident : $b:expr,)* ] $t:ident ) => {
@@ -0,0 +1,37 @@
+use Base;
+use Castable;
+use UnsafeCastable;
+
+/// Implemented by `inherit!` for every struct of a `sealed` block.
+///
+/// `Kind` and `KindMut` are the enums generated for the block, with a variant for each of its
+/// concrete types. `Handle::kind` and `HandleMut::kind_mut` return the variant of the object's
+/// bottom most sub-type, so it can be matched exhaustively. Every struct of the block must
+/// descend from its root, and no struct outside the block can inherit from one inside it, so
+/// every object has a variant.
+pub trait Sealed: Castable {
+    /// A reference to an object of the sealed hierarchy, as its bottom most sub-type.
+    type Kind<'a>;
+
+    /// A mutable reference to an object of the sealed hierarchy, as its bottom most sub-type.
+    type KindMut<'a>;
+
+    /// Returns the variant of the bottom most sub-type of `object`, the whole object.
+    #[doc(hidden)]
+    fn kind_of(object: &dyn UnsafeCastable) -> Self::Kind<'_>;
+
+    /// Mutable variant of `kind_of`.
+    #[doc(hidden)]
+    fn kind_of_mut(object: &mut dyn UnsafeCastable) -> Self::KindMut<'_>;
+}
+
+/// Implemented by `inherit!` for the types that `Sub` may inherit from, which `inherit!` checks
+/// for each struct.
+///
+/// A type outside any sealed block is `Inheritable` by every type. A type of a sealed block is
+/// only `Inheritable` by the types of its own block, so it can't gain sub-types that its `Kind`
+/// enum has no variant for.
+#[doc(hidden)]
+pub trait Inheritable<Sub: ?Sized> {}
+
+impl<Sub: ?Sized> Inheritable<Sub> for Base {}
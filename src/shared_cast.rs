@@ -0,0 +1,153 @@
+use base::{Ancestry, Base};
+use Castable;
+use Constructable;
+use Handle;
+use UnsafeCastable;
+
+use std::ops::Deref;
+use std::ptr;
+use std::rc::{self, Rc};
+use std::sync::{self, Arc};
+
+// An object shared by reference-counted handles, along with its ancestry, which the object's
+// `Base` refers to. Keeping them in one allocation keeps the ancestry alive exactly as long as
+// the object, however many weak handles there are.
+pub(crate) struct Shared<O: ?Sized> {
+    pub(crate) ancestry: Arc<Ancestry>,
+    pub(crate) object: O
+}
+
+// RcCast and ArcCast only differ in their pointer type, so both are generated here, along with
+// their weak counterparts.
+macro_rules! shared_cast {
+    ($(#[$doc:meta])* $name:ident, $ptr:ident<$obj:ty>,
+     $init:ident, T: Constructable $(+ $bound:ident)*;
+     $(#[$wdoc:meta])* $weak:ident, $wmod:ident) => {
+        $(#[$doc])*
+        pub struct $name<T: Castable> {
+            __shared__: $ptr<Shared<$obj>>,
+            ptr: *const T
+        }
+
+        impl<T: Castable> $name<T> {
+            /// Dynamically casts to another shared handle of the same object, up or down,
+            /// returning None on failure.
+            pub fn cast<U: Castable>(&self) -> Option<$name<U>> {
+                let object = &self.__shared__.object as *const $obj as *mut $obj;
+                let ptr = unsafe { Base::layer_of::<U, _>(object) }?;
+                Some($name { __shared__: self.__shared__.clone(), ptr })
+            }
+
+            /// Returns true if both handles share the same object.
+            pub fn ptr_eq(this: &Self, other: &$name<T>) -> bool {
+                $ptr::ptr_eq(&this.__shared__, &other.__shared__)
+            }
+
+            /// Returns the number of strong handles to the object.
+            pub fn strong_count(this: &Self) -> usize {
+                $ptr::strong_count(&this.__shared__)
+            }
+
+            /// Creates a non-owning handle to the object.
+            pub fn downgrade(this: &Self) -> $weak<T> {
+                $weak { __weak__: $ptr::downgrade(&this.__shared__), ptr: this.ptr }
+            }
+
+            pub(crate) fn $init(t: T) -> Self where T: Constructable $(+ $bound)* {
+                let mut shared = $ptr::new(Shared { ancestry: Ancestry::of(&t), object: t });
+                {
+                    let shared = $ptr::get_mut(&mut shared).unwrap();
+                    unsafe { Base::init_instance(&mut shared.object, &shared.ancestry) };
+                }
+                let shared:$ptr<Shared<$obj>> = shared;
+                let ptr = unsafe { ptr::addr_of!((*$ptr::as_ptr(&shared)).object) } as *const T;
+                $name { __shared__: shared, ptr }
+            }
+        }
+
+        impl<T: Castable> Clone for $name<T> {
+            fn clone(&self) -> Self {
+                $name { __shared__: self.__shared__.clone(), ptr: self.ptr }
+            }
+        }
+
+        impl<T: Castable> Deref for $name<T> {
+            type Target = T;
+            fn deref(&self) -> &T {
+                unsafe { &*self.ptr }
+            }
+        }
+
+        unsafe impl<T: Castable> Handle for $name<T> {
+            fn object(&self) -> &dyn UnsafeCastable {
+                self.__shared__.object.as_dyn()
+            }
+        }
+
+        impl<T: Constructable $(+ $bound)*> From<T> for $name<T> {
+            fn from(t: T) -> Self {
+                $name::$init(t)
+            }
+        }
+
+        $(#[$wdoc])*
+        pub struct $weak<T: Castable> {
+            __weak__: $wmod::Weak<Shared<$obj>>,
+            ptr: *const T
+        }
+
+        impl<T: Castable> $weak<T> {
+            /// Returns a strong handle to the object, or None if it has been dropped.
+            pub fn upgrade(&self) -> Option<$name<T>> {
+                self.__weak__.upgrade().map(|shared| $name { __shared__: shared, ptr: self.ptr })
+            }
+
+            /// Returns a strong handle to the object, dynamically downcast to `U`.
+            ///
+            /// Returns None if the object has been dropped, or if the downcast fails.
+            pub fn upgrade_as<U: Castable>(&self) -> Option<$name<U>> {
+                self.upgrade()?.cast()
+            }
+        }
+
+        impl<T: Castable> Clone for $weak<T> {
+            fn clone(&self) -> Self {
+                $weak { __weak__: self.__weak__.clone(), ptr: self.ptr }
+            }
+        }
+    };
+}
+
+shared_cast! {
+    /// A correctly constructed, reference-counted castable object.
+    ///
+    /// Created by `Constructable::init_rc()`. Cloning an `RcCast` shares the object, and
+    /// `downcast` returns another `RcCast` viewing the same object as a sub-type.
+    RcCast, Rc<dyn UnsafeCastable>, new_rc, T: Constructable;
+
+    /// A non-owning handle to an object owned by `RcCast`s.
+    ///
+    /// Created by `RcCast::downgrade()`. `upgrade_as` upgrades and downcasts in one step.
+    WeakCast, rc
+}
+
+shared_cast! {
+    /// A correctly constructed, atomically reference-counted castable object.
+    ///
+    /// Created by `Constructable::init_arc()`. Cloning an `ArcCast` shares the object, and
+    /// `downcast` returns another `ArcCast` viewing the same object as a sub-type.
+    ///
+    /// The object must be `Send` and `Sync`, so an `ArcCast` can be shared between threads.
+    ArcCast, Arc<dyn UnsafeCastable + Send + Sync>, new_arc, T: Constructable + Send + Sync;
+
+    /// A non-owning handle to an object owned by `ArcCast`s.
+    ///
+    /// Created by `ArcCast::downgrade()`. `upgrade_as` upgrades and downcasts in one step.
+    WeakArcCast, sync
+}
+
+// the layer pointer points into the object, which is Send + Sync
+unsafe impl<T: Castable> Send for ArcCast<T> {}
+unsafe impl<T: Castable> Sync for ArcCast<T> {}
+unsafe impl<T: Castable> Send for WeakArcCast<T> {}
+unsafe impl<T: Castable> Sync for WeakArcCast<T> {}
@@ -0,0 +1,34 @@
+use Castable;
+
+/// A compile-time marker that `Self` is `T`, or inherits from `T`.
+///
+/// The `inherit!` and `impl_inherit!` macros implement this trait for every super-type of a
+/// type, transitively up to `Base`, and every type is trivially a sub-type of itself. Unlike
+/// downcasting, which is checked at runtime, a `SubtypeOf` bound lets the compiler check that
+/// an upcast is valid.
+///
+/// ```
+/// # #![allow(dead_code)]
+/// # #[macro_use]
+/// # extern crate castable;
+/// # use castable::{Base, Cast, Constructable, SubtypeOf};
+/// # inherit! {
+/// #     #[derive(Default)] struct SuperType;
+/// #     #[derive(Default)] struct SubType: SuperType;
+/// # }
+/// fn generic<T: SubtypeOf<SuperType>>(t: Cast<T>) -> Cast<SuperType> {
+///     t.upcast()
+/// }
+///
+/// # fn main() {
+/// let super_type = generic(SubType::default().init());
+/// let base:Cast<Base> = super_type.upcast();
+/// # }
+/// ```
+///
+/// # Safety
+///
+/// `Self` must contain a `T` layer. Implement this trait with the macros instead of by hand.
+pub unsafe trait SubtypeOf<T: ?Sized>: Castable {}
+
+unsafe impl<T: Castable + ?Sized> SubtypeOf<T> for T {}
@@ -0,0 +1,86 @@
+use base::{Ancestry, Base};
+use shared_cast::Shared;
+use Castable;
+use Constructable;
+use UnsafeCastable;
+use {Handle, HandleMut};
+
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, LockResult, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+type Object = dyn UnsafeCastable + Send + Sync;
+
+locked_cast! {
+    /// A correctly constructed castable object, shared between threads behind a `RwLock`.
+    ///
+    /// Created by `Constructable::init_rwlock()`. `read()` and `write()` lock the whole object, and
+    /// return guards that deref to `T`. Downcasting through a guard reaches the rest of the
+    /// object under the same lock, so no thread can observe another mid-mutation.
+    ///
+    /// ```
+    /// # #![allow(dead_code)]
+    /// # #[macro_use]
+    /// # extern crate castable;
+    /// # use castable::{Constructable, HandleMut, SyncCast};
+    /// # inherit! {
+    /// #     #[derive(Default)] struct SuperType { count: u32 }
+    /// #     #[derive(Default)] struct SubType: SuperType { sub_count: u32 }
+    /// # }
+    /// # fn main() {
+    /// let super_type:SyncCast<SuperType> = SubType::default().init_rwlock().cast().unwrap();
+    /// let shared = super_type.clone();
+    /// std::thread::spawn(move || {
+    ///     let mut guard = shared.write().unwrap();
+    ///     guard.count += 1;
+    ///     guard.downcast_mut::<SubType>().unwrap().sub_count += 1;
+    /// }).join().unwrap();
+    /// assert_eq!(super_type.read().unwrap().count, 1);
+    /// # }
+    /// ```
+    SyncCast, Arc, RwLock<Object>, PhantomData(fn() -> T);
+
+    /// A read lock on a `SyncCast<T>`, which derefs to `T`.
+    SyncCastReadGuard, RwLockReadGuard;
+
+    /// A write lock on a `SyncCast<T>`, which derefs to `T`.
+    SyncCastWriteGuard, RwLockWriteGuard
+}
+
+impl<T: Castable> SyncCast<T> {
+    pub(crate) fn new(t: T) -> Self where T: Constructable + Send + Sync {
+        let mut shared = Arc::new(Shared { ancestry: Ancestry::of(&t), object: RwLock::new(t) });
+        {
+            let shared = Arc::get_mut(&mut shared).unwrap();
+            let t = shared.object.get_mut().unwrap();
+            unsafe { Base::init_instance(t, &shared.ancestry) };
+        }
+        SyncCast { __shared__: shared, offset: 0, p: PhantomData }
+    }
+
+    /// Locks the object for reading, blocking until no thread is writing to it.
+    pub fn read(&self) -> LockResult<SyncCastReadGuard<'_, T>> {
+        let offset = self.offset;
+        let guard = |guard| SyncCastReadGuard { guard, offset, p: PhantomData };
+        match self.__shared__.object.read() {
+            Ok(g) => Ok(guard(g)),
+            Err(e) => Err(PoisonError::new(guard(e.into_inner())))
+        }
+    }
+
+    /// Locks the object for writing, blocking until no other thread holds the lock.
+    pub fn write(&self) -> LockResult<SyncCastWriteGuard<'_, T>> {
+        let offset = self.offset;
+        let guard = |guard| SyncCastWriteGuard { guard, offset, p: PhantomData };
+        match self.__shared__.object.write() {
+            Ok(g) => Ok(guard(g)),
+            Err(e) => Err(PoisonError::new(guard(e.into_inner())))
+        }
+    }
+}
+
+impl<T: Constructable + Send + Sync> From<T> for SyncCast<T> {
+    fn from(t: T) -> Self {
+        SyncCast::new(t)
+    }
+}
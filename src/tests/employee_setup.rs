@@ -21,3 +21,17 @@ impl Employee {
         self.hours * self.pay
     }
 }
+
+// the Salesperson most tests start from; init() it, or use another owner
+pub fn salesperson() -> Salesperson {
+    construct!( raw Salesperson {
+        sales: 4,
+        sup.. Employee {
+            hours: 21.5,
+            pay: 15.25,
+            sup.. Person {
+                name: "John".to_string()
+            }
+        }
+    })
+}
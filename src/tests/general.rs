@@ -21,8 +21,8 @@ fn general_casting() {
     // implicit downcast to Person
     let p:&Person = &s;
     assert!(p.get_ident() == Person::ident());
-    // explicit (up) cast to Employee
-    let e:&Employee = p.downcast().unwrap();
+    // explicit (up) cast to Employee, through the Cast holding the whole object
+    let e:&Employee = s.downcast().unwrap();
     assert!(e.get_ident() == Employee::ident());
 }
 
@@ -49,8 +49,216 @@ fn mutable_casting() {
     {
         let p2:&mut Person = &mut s2;
         p2.name = "Dave".to_string();
-        let e2:&mut Employee = p2.downcast_mut().unwrap();
+        let e2:&mut Employee = s2.downcast_mut().unwrap();
         e2.hours += 3.0;
     }
     assert_eq!(format!("{} {}", s2.name, s2.hours), "Dave 20");
 }
+
+#[test]
+fn ancestry_downcasting() {
+    let s = salesperson().init();
+    assert_eq!(Base::depth(), 0);
+    assert_eq!(Salesperson::depth(), 3);
+    // every layer can be reached directly, whatever the Cast is viewed as
+    let p:&Cast<Person> = s.as_super();
+    assert_eq!(p.downcast::<Salesperson>().unwrap().sales, 4);
+    assert_eq!(p.downcast::<Employee>().unwrap().hours, 21.5);
+    let e:&Cast<Employee> = s.as_super();
+    assert_eq!(e.downcast::<Person>().unwrap().name, "John");
+    // a type at the right depth, but from another hierarchy, is rejected
+    let e = construct!( Employee {
+        hours: 1.0,
+        pay: 1.0,
+        sup.. Person {
+            name: "Jane".to_string()
+        }
+    });
+    let p:Cast<Person> = e.upcast();
+    assert!(p.downcast::<Salesperson>().is_none());
+    assert!(p.downcast::<Employee>().is_some());
+}
+
+inherit!{
+    #[derive(Default)]
+    #[inherit(Debug)]
+    struct Shape {
+        name: &'static str
+    }
+
+    #[inherit(Debug)]
+    #[derive(Default)]
+    struct Square: Shape {
+        side: u32
+    }
+}
+
+inherit!{
+    struct Undebuggable: Person {
+        _secret: u32
+    }
+}
+
+#[test]
+fn flattened_debug() {
+    let s: Cast<Shape> = construct!( Square as Shape {
+        side: 3,
+        sup.. Shape {
+            name: "square"
+        }
+    });
+    assert_eq!(format!("{:?}", s), r#"Square { name: "square", side: 3 }"#);
+    assert_eq!(format!("{:?}", *s), r#"Shape { name: "square" }"#);
+    assert_eq!(format!("{:?}", s.get_base()), "Base { instance: Some(Square) }");
+    assert_eq!(format!("{:?}", Base::default()), "Base { instance: None }");
+
+    // layers that only derive Debug are flattened too
+    let p: Cast<Person> = salesperson().init().upcast();
+    assert_eq!(format!("{:?}", p),
+        r#"Salesperson { name: "John", hours: 21.5, pay: 15.25, sales: 4 }"#);
+
+    // layers without a Debug of their own are left out
+    let p: Cast<Person> = construct!( Undebuggable as Person {
+        _secret: 7,
+        sup.. Person {
+            name: "Nobody".to_string()
+        }
+    });
+    assert_eq!(format!("{:?}", p), r#"Undebuggable { name: "Nobody", .. }"#);
+}
+
+#[test]
+fn type_introspection() {
+    use std::any::TypeId;
+
+    assert_eq!(Salesperson::type_name(), "Salesperson");
+    assert_eq!(Employee::hierarchy(), vec![
+        (TypeId::of::<Employee>(), "Employee"),
+        (TypeId::of::<Person>(), "Person"),
+        (TypeId::of::<Base>(), "Base")
+    ]);
+
+    let p: Cast<Person> = salesperson().init().upcast();
+    assert_eq!(p.get_type_name(), "Person");
+    assert_eq!(p.dynamic_type_name(), "Salesperson");
+    assert_eq!(p.dynamic_hierarchy(), Salesperson::hierarchy());
+
+    // an incorrectly constructed value only knows about itself
+    let e = (*p.downcast::<Employee>().unwrap()).clone();
+    assert_eq!(e.dynamic_type_name(), "Employee");
+    assert_eq!(e.dynamic_hierarchy(), Employee::hierarchy());
+    assert_eq!(Base::default().dynamic_hierarchy(), Base::hierarchy());
+}
+
+#[test]
+fn layer_iteration() {
+    let mut p: Cast<Person> = salesperson().init().upcast();
+    let names = |layers: Layers| layers.map(|l| l.get_type_name()).collect::<Vec<_>>();
+    assert_eq!(names(p.ancestors()), ["Person", "Base"]);
+    assert_eq!(names(p.descendants_view()), ["Salesperson", "Employee", "Person"]);
+    assert_eq!(names(p.get_base().ancestors()), ["Base"]);
+
+    {
+        let mut layers = p.descendants_view_mut();
+        while let Some(layer) = layers.next_layer() {
+            if let Some(e) = layer.as_any_mut().downcast_mut::<Employee>() {
+                e.hours += 1.0;
+            }
+        }
+    }
+    assert_eq!(p.downcast::<Employee>().map(|e| e.hours), Some(22.5));
+}
+
+#[test]
+fn type_tests() {
+    use std::any::TypeId;
+
+    let p: Cast<Person> = construct!( Employee as Person {
+        hours: 12.5,
+        pay: 15.25,
+        sup.. Person {
+            name: "John".to_string()
+        }
+    });
+    assert!(p.is::<Person>());
+    assert!(p.is::<Employee>());
+    assert!(p.is::<Base>());
+    assert!(!p.is::<Salesperson>());
+    assert!(p.is_exactly::<Employee>());
+    assert!(!p.is_exactly::<Person>());
+    assert_eq!(p.dynamic_type_id(), TypeId::of::<Employee>());
+    assert_eq!(p.get_base().dynamic_type_id(), TypeId::of::<Employee>());
+
+    // an incorrectly constructed value is only itself and its super-types
+    let e = (*p.downcast::<Employee>().unwrap()).clone();
+    assert!(e.is::<Employee>());
+    assert!(e.is_exactly::<Employee>());
+    assert!(e.is::<Person>());
+    assert!(e.is::<Base>());
+    assert!(!e.is::<Salesperson>());
+}
+
+#[test]
+fn cast_errors() {
+    let mut p: Cast<Person> = construct!( Employee as Person {
+        hours: 12.5,
+        pay: 15.25,
+        sup.. Person {
+            name: "John".to_string()
+        }
+    });
+    assert!(p.try_downcast::<Employee>().is_ok());
+    assert!(p.try_downcast_mut::<Employee>().is_ok());
+
+    let err = p.try_downcast::<Salesperson>().unwrap_err();
+    assert_eq!(err.requested(), "Salesperson");
+    assert_eq!(err.actual(), "Employee");
+    assert!(!err.is_uninitialized());
+    assert_eq!(err.to_string(), "cannot cast Employee to Salesperson");
+
+    // assigning to the Person layer replaces the object's Base with an uninitialized one
+    let mut p: Cast<Person> = construct!( Person {
+        name: "John".to_string()
+    });
+    *p = construct!( raw Person {
+        name: "Jane".to_string()
+    });
+    let err = p.try_downcast::<Employee>().unwrap_err();
+    assert!(err.is_uninitialized());
+    assert_eq!(err.to_string(),
+        "cannot cast Person to Employee: the object's Base was moved out of it");
+}
+
+#[test]
+#[should_panic(expected = "Cast::new: cannot cast Person to Employee")]
+fn cast_error_panics() {
+    let raw = construct!( raw Person {
+        name: "John".to_string()
+    });
+    let _: Cast<Employee> = Cast::new(Box::new(raw));
+}
+
+#[test]
+fn uninitialized_objects() {
+    let raw = || construct!( raw Employee {
+        hours: 12.5,
+        pay: 15.25,
+        sup.. Person {
+            name: "John".to_string()
+        }
+    });
+    let e = raw();
+    assert!(!e.is_initialized());
+    assert!(!e.get_base().is_initialized());
+    assert!(!upcast!(e as Person).is::<Employee>());
+    // wrapping it in a Cast initializes it
+    let mut c: Cast<Employee> = Cast::new(Box::new(raw()));
+    assert!(c.is_initialized());
+    assert!(c.downcast_mut::<Employee>().is_some());
+    assert!(c.downcast::<Person>().is_some());
+
+    let e = raw().init();
+    assert!(e.is_initialized());
+    assert!(e.get_base().is_initialized());
+    assert!(e.as_super::<Person>().downcast::<Employee>().is_some());
+}
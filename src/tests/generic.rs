@@ -1,6 +1,8 @@
 use super::employee_setup::*;
 use super::super::Cast;
-use super::super::Castable;
+use super::super::Constructable;
+use super::super::{Handle, HandleMut};
+use super::super::SubtypeOf;
 
 fn inspect_employee(e: &Employee) {
     println!("{} has worked {} hours this week", e.name, e.hours);
@@ -9,22 +11,13 @@ fn inspect_employee(e: &Employee) {
 #[test]
 fn generic_casting() {
     // using the generic keyword, construct! returns a Box<Inheritable>
-    let ref g:Cast<Employee> = construct!( Salesperson as Employee {
-        sales: 4,
-        sup.. Employee {
-            hours: 12.5,
-            pay: 15.25,
-            sup.. Person {
-                name: "John 'Lazy-Worker' Doe".to_string()
-            }
-        }
-    });
+    let g: &Cast<Employee> = &salesperson().init().upcast();
     // we can cast as Salesperson
     assert!(g.downcast::<Salesperson>().is_some());
 
     inspect_employee(g);
     // put a regular employee in the same variable
-    let ref g:Cast<Employee> = construct!( Employee {
+    let g: &Cast<Employee> = &construct!( Employee {
         hours: 42.0,
         pay: 16.15,
         sup.. Person {
@@ -35,3 +28,197 @@ fn generic_casting() {
     assert!(g.downcast::<Salesperson>().is_none());
     inspect_employee(g);
 }
+
+#[test]
+fn owned_downcasting() {
+    let g:Cast<Employee> = construct!( Employee {
+        hours: 42.0,
+        pay: 16.15,
+        sup.. Person {
+            name: "Billy 'Hard-Worker' Smith".to_string()
+        }
+    });
+    // a plain Employee is not a Salesperson, and the Cast is handed back
+    let g = match g.try_into_sub::<Salesperson>() {
+        Ok(_) => panic!("Employee downcast to Salesperson"),
+        Err(g) => g
+    };
+    assert_eq!(g.hours, 42.0);
+
+    let g:Cast<Employee> = salesperson().init().upcast();
+    let s:Cast<Salesperson> = g.try_into_sub().ok().unwrap();
+    assert_eq!(s.sales, 4);
+}
+
+#[test]
+fn cast_as_upcasts() {
+    let s:Cast<Salesperson> = salesperson().init();
+    let e:Cast<Employee> = s.cast_as();
+    assert_eq!(e.hours, 21.5);
+    let p:Cast<Person> = e.cast_as();
+    // going back down is checked at run time
+    assert_eq!(p.try_into_sub::<Salesperson>().ok().unwrap().sales, 4);
+}
+
+fn total_hours<T: SubtypeOf<Employee>>(staff: Vec<Cast<T>>) -> f64 {
+    staff.into_iter().map(|e| e.upcast::<Employee>().hours).sum()
+}
+
+#[test]
+fn static_upcasting() {
+    let s = salesperson().init();
+    let p:Cast<Person> = s.upcast();
+    assert_eq!(p.name, "John");
+    // the upcast Cast can still be downcast back
+    let s = p.try_into_sub::<Salesperson>().ok().unwrap();
+    assert_eq!(total_hours(vec![s]), 21.5);
+}
+
+#[test]
+fn collection_upcasting() {
+    let staff = vec![
+        salesperson().init(),
+        {
+            let mut s = salesperson();
+            s.sales = 9;
+            s.name = "Billy 'Hard-Worker' Smith".to_string();
+            s.init()
+        }
+    ];
+    let people:&[Cast<Person>] = Cast::upcast_slice(&staff);
+    assert_eq!(people[1].name, "Billy 'Hard-Worker' Smith");
+    assert!(people[0].downcast::<Salesperson>().is_some());
+
+    let addr = staff.as_ptr() as usize;
+    let mut people:Vec<Cast<Person>> = Cast::upcast_vec(staff);
+    // the Vec is reinterpreted in place
+    assert_eq!(people.as_ptr() as usize, addr);
+    people.push(construct!( Person { name: "Jane".to_string() }));
+    let sales:Vec<u32> = people.iter()
+        .filter_map(|p| p.downcast::<Salesperson>().map(|s| s.sales))
+        .collect();
+    assert_eq!(sales, vec![4, 9]);
+}
+
+fn describe(p: &Cast<Person>) -> String {
+    match p.downcast::<Salesperson>() {
+        Some(s) => format!("{} (sales: {})", p.name, s.sales),
+        None => p.name.clone()
+    }
+}
+
+#[test]
+fn borrowed_upcasting() {
+    let mut s = salesperson().init();
+    assert_eq!(describe(s.as_super()), "John (sales: 4)");
+    {
+        let e:&mut Cast<Employee> = unsafe { s.as_super_mut() };
+        e.hours += 1.0;
+    }
+    assert_eq!(s.hours, 22.5);
+}
+
+inherit!{
+    struct Unclonable: Person {
+        _secret: u32
+    }
+}
+
+#[test]
+fn polymorphic_cloning() {
+    let people: Vec<Cast<Person>> = vec![
+        salesperson().init().upcast(),
+        construct!( Person {
+            name: "Jane Doe".to_string()
+        })
+    ];
+    let mut copies: Vec<Cast<Person>> = people.iter().map(|p| p.clone_dyn().unwrap()).collect();
+    copies[0].name = "John Doe".to_string();
+    assert_eq!(people[0].name, "John");
+    // the clone is still a Salesperson, and independently initialized
+    assert_eq!(copies[0].downcast::<Salesperson>().map(|s| s.sales), Some(4));
+    assert_eq!(copies[0].downcast::<Salesperson>().map(|s| &s.name[..]), Some("John Doe"));
+    assert!(copies[1].downcast::<Employee>().is_none());
+
+    let u: Cast<Person> = construct!( Unclonable as Person {
+        _secret: 7,
+        sup.. Person {
+            name: "Nobody".to_string()
+        }
+    });
+    assert!(u.clone_dyn().is_none());
+}
+
+inherit!{
+    sealed Account as AccountKind, AccountKindMut;
+
+    #[derive(Default)]
+    struct Account {
+        balance: i64
+    }
+
+    #[derive(Default)]
+    struct Savings: Account {
+        rate: i64
+    }
+
+    struct Bonus: Savings {
+        bonus: i64
+    }
+}
+
+fn interest(a: &Cast<Account>) -> i64 {
+    // adding a type to the sealed block makes this match fail to compile
+    match a.kind() {
+        AccountKind::Account(a) => a.balance / 100,
+        AccountKind::Savings(s) => s.balance * s.rate / 100,
+        AccountKind::Bonus(b) => b.balance * b.rate / 100 + b.bonus
+    }
+}
+
+#[test]
+fn sealed_matching() {
+    let mut accounts: Vec<Cast<Account>> = vec![
+        construct!( Bonus as Account {
+            bonus: 5,
+            sup.. Savings {
+                rate: 2,
+                sup.. Account {
+                    balance: 100
+                }
+            }
+        }),
+        construct!( Savings as Account {
+            rate: 3,
+            sup.. Account {
+                balance: 200
+            }
+        }),
+        construct!( Account {
+            balance: 300
+        })
+    ];
+    let total: Vec<i64> = accounts.iter().map(interest).collect();
+    assert_eq!(total, [7, 6, 3]);
+
+    for a in &mut accounts {
+        match a.kind_mut() {
+            AccountKindMut::Account(a) => a.balance += 100,
+            AccountKindMut::Savings(s) => s.rate += 1,
+            AccountKindMut::Bonus(b) => b.bonus += 1
+        }
+    }
+    let total: Vec<i64> = accounts.iter().map(interest).collect();
+    assert_eq!(total, [8, 8, 4]);
+
+    // a Cast viewed as Savings still knows it is a Bonus
+    let s: Cast<Savings> = accounts.remove(0).try_into_sub().ok().unwrap();
+    assert!(matches!(s.kind(), AccountKind::Bonus(_)));
+
+    // so does a boxed raw object, once wrapped
+    let raw = construct!( raw Savings {
+        rate: 1
+    });
+    let s: Cast<Savings, Savings> = Cast::new(Box::new(raw));
+    assert!(matches!(s.kind(), AccountKind::Savings(_)));
+}
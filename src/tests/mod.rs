@@ -7,3 +7,7 @@
 pub mod employee_setup;
 pub mod generic;
 pub mod general;
+pub mod shared;
+pub mod soundness;
+pub mod threads;
+pub mod virtuals;
@@ -0,0 +1,78 @@
+use super::employee_setup::*;
+use super::super::*;
+
+#[test]
+fn rc_casting() {
+    let s = salesperson().init_rc();
+    let p:RcCast<Person> = s.cast().unwrap();
+    assert_eq!(p.name, "John");
+    assert_eq!(RcCast::strong_count(&s), 2);
+    // casting a shared handle returns another shared handle of the same object
+    let e:RcCast<Employee> = p.cast().unwrap();
+    assert_eq!(e.income(), 21.5 * 15.25);
+    let s2:RcCast<Salesperson> = e.cast().unwrap();
+    assert!(RcCast::ptr_eq(&s, &s2));
+    // the handle also lends out the layers of its object
+    let s:&Salesperson = p.downcast().unwrap();
+    assert_eq!(s.sales, 4);
+
+    let e = construct!( raw Employee {
+        hours: 1.0,
+        pay: 1.0,
+        sup.. Person {
+            name: "Jane".to_string()
+        }
+    }).init_rc();
+    assert!(e.cast::<Salesperson>().is_none());
+}
+
+#[test]
+fn arc_casting() {
+    let e:ArcCast<Employee> = salesperson().init_arc().cast().unwrap();
+    let s = e.clone().cast::<Salesperson>().unwrap();
+    assert_eq!(s.sales, 4);
+    assert_eq!(ArcCast::strong_count(&e), 2);
+}
+
+#[test]
+fn weak_casting() {
+    let p:RcCast<Person> = salesperson().init_rc().cast().unwrap();
+    let w = RcCast::downgrade(&p);
+    assert_eq!(w.upgrade().unwrap().name, "John");
+    // a weak Person handle upgrades straight to a Salesperson
+    assert_eq!(w.upgrade_as::<Salesperson>().unwrap().sales, 4);
+    assert_eq!(RcCast::strong_count(&p), 1);
+    drop(p);
+    assert!(w.upgrade().is_none());
+    assert!(w.upgrade_as::<Salesperson>().is_none());
+
+    let p:RcCast<Person> = construct!( raw Person { name: "Jane".to_string() }).init_rc();
+    let w = RcCast::downgrade(&p);
+    assert!(w.upgrade_as::<Employee>().is_none());
+}
+
+#[test]
+fn cell_casting() {
+    let p:CastCell<Person> = salesperson().init_cell().cast().unwrap();
+    let s = p.cast::<Salesperson>().unwrap();
+    assert!(CastCell::ptr_eq(&s, &p.cast().unwrap()));
+    {
+        let mut guard = p.borrow_mut();
+        guard.name.push('!');
+        guard.downcast_mut::<Employee>().unwrap().hours += 1.0;
+        // a mutable borrow through the Person view blocks the Salesperson view
+        assert!(s.try_borrow().is_err());
+        // but not casting the handles, which doesn't borrow the object
+        assert!(s.cast::<Employee>().is_some());
+        assert!(p.cast::<Salesperson>().is_some());
+    }
+    {
+        let guard = s.borrow();
+        assert_eq!(guard.name, "John!");
+        assert_eq!(guard.hours, 22.5);
+        // shared borrows coexist across views, but block mutable ones
+        assert_eq!(p.borrow().downcast::<Salesperson>().unwrap().sales, 4);
+        assert!(p.try_borrow_mut().is_err());
+    }
+    assert!(p.cast::<Salesperson>().is_some());
+}
@@ -0,0 +1,148 @@
+// These tests exercise every pointer path in the crate, and are meant to be run under Miri:
+//
+//     cargo +nightly miri test
+use super::employee_setup::*;
+use super::super::*;
+
+#[test]
+fn reborrow_after_downcast_mut() {
+    let mut s = salesperson().init();
+    {
+        // writes to the Person layer through a wider downcast reference
+        let e:&mut Employee = s.downcast_mut().unwrap();
+        e.name.push('!');
+        e.hours += 1.0;
+    }
+    // the owner must still be usable afterwards
+    s.name.push('?');
+    {
+        let p:&mut Cast<Person> = unsafe { s.as_super_mut() };
+        let s:&mut Salesperson = p.downcast_mut().unwrap();
+        s.sales += 1;
+        s.name.push('.');
+    }
+    s.name.push('.');
+    assert_eq!(s.name, "John!?..");
+    assert_eq!(s.hours, 22.5);
+    assert_eq!(s.sales, 5);
+}
+
+#[test]
+fn shared_downcasts_alongside_each_other() {
+    let s = salesperson().init();
+    let p:&Cast<Person> = s.as_super();
+    let e:&Employee = p.downcast().unwrap();
+    let s2:&Salesperson = p.downcast().unwrap();
+    let p2:&Person = s.downcast().unwrap();
+    assert_eq!(p.name, p2.name);
+    assert_eq!(e.hours, s2.hours);
+}
+
+#[test]
+fn trait_object_upcasts() {
+    let mut s = salesperson().init();
+    {
+        let any = unsafe { s.u_upcast_mut(Person::ident()) }.unwrap();
+        any.downcast_mut::<Person>().unwrap().name.push('!');
+    }
+    // a layer can't reach its sub-types
+    let p:&Person = &s;
+    assert!(unsafe { p.u_upcast(Salesperson::ident()) }.is_none());
+    assert_eq!(s.name, "John!");
+}
+
+#[test]
+fn moving_owners() {
+    let mut s = salesperson().init();
+    s.sales += 1;
+    // moving the Cast must not invalidate its layer pointer or the object's Base
+    let mut staff = vec![s];
+    staff[0].hours = 1.0;
+    let s = staff.pop().unwrap();
+    let e:Cast<Employee> = s.upcast();
+    let mut s:Cast<Salesperson> = e.try_into_sub().ok().unwrap();
+    s.name.push('!');
+    let mut p:Cast<Person> = s.upcast();
+    p.downcast_mut::<Salesperson>().unwrap().sales += 1;
+    let s:Cast<Salesperson> = p.try_into_sub().ok().unwrap();
+    assert_eq!(s.sales, 6);
+    assert_eq!(s.hours, 1.0);
+}
+
+#[test]
+fn shared_owners() {
+    let p:RcCast<Person> = salesperson().init_rc().cast().unwrap();
+    let w = RcCast::downgrade(&p);
+    let s = w.upgrade_as::<Salesperson>().unwrap();
+    let e:&Employee = s.downcast().unwrap();
+    assert_eq!(e.name, p.name);
+    drop(p);
+    drop(s);
+    assert!(w.upgrade().is_none());
+}
+
+#[test]
+fn swapped_layers() {
+    use std::mem;
+    let mut a = salesperson().init();
+    let mut b = construct!( Employee {
+        hours: 40.0,
+        pay: 20.0,
+        sup.. Person {
+            name: "Jane".to_string()
+        }
+    });
+    // each Person layer now carries the other object's Base
+    mem::swap::<Person>(&mut a, &mut b);
+    assert_eq!(a.name, "Jane");
+    assert_eq!(b.name, "John");
+    assert!(a.downcast::<Employee>().is_none());
+    assert!(b.downcast_mut::<Person>().is_none());
+    // the owners still know their own layers
+    assert!(a.downcast::<Salesperson>().is_some());
+    assert_eq!(a.sales, 4);
+    assert_eq!(b.hours, 40.0);
+    assert!(b.try_downcast::<Salesperson>().is_err());
+
+    // a layer moved out of its object is no longer correctly constructed
+    let mut s = salesperson().init();
+    let e = mem::replace::<Employee>(&mut s, construct!( raw Employee {
+        hours: 0.0,
+        pay: 0.0,
+        sup.. Person {
+            name: "Nobody".to_string()
+        }
+    }));
+    assert!(!e.is::<Salesperson>());
+    assert!(s.downcast::<Person>().is_none());
+}
+
+#[test]
+fn stale_bases() {
+    use std::mem;
+    // a Base moved out of its object stays uninitialized once the object is dropped, even if
+    // it's put back together at the address the object had
+    let mut s = salesperson().init();
+    let e = mem::replace::<Employee>(&mut s, construct!( raw Employee {
+        hours: 0.0,
+        pay: 0.0,
+        sup.. Person {
+            name: "Nobody".to_string()
+        }
+    }));
+    drop(s);
+    let mut raw = construct!( raw Salesperson {
+        sales: 4,
+        sup.. Employee {
+            hours: 0.0,
+            pay: 0.0,
+            sup.. Person {
+                name: "Nobody".to_string()
+            }
+        }
+    });
+    raw.__super__ = e;
+    let raw = Box::new(raw);
+    assert!(!raw.is_initialized());
+    assert!(!raw.get_base().is_initialized());
+}
@@ -0,0 +1,86 @@
+use super::employee_setup::*;
+use super::super::*;
+
+use std::thread;
+
+#[test]
+fn send_casting() {
+    let p:Cast<Person, dyn UnsafeCastable + Send> = salesperson().init_send().upcast();
+    let sales = thread::spawn(move || {
+        let mut s = p.try_into_sub::<Salesperson>().ok().unwrap();
+        s.sales += 1;
+        s
+    }).join().unwrap();
+    assert_eq!(sales.sales, 5);
+}
+
+#[test]
+fn sync_casting() {
+    let s = salesperson().init_sync();
+    let e:&Cast<Employee, _> = s.as_super();
+    thread::scope(|scope| {
+        for _ in 0..2 {
+            scope.spawn(|| assert_eq!(e.downcast::<Salesperson>().unwrap().sales, 4));
+        }
+    });
+}
+
+#[test]
+fn arc_casting_across_threads() {
+    let p:ArcCast<Person> = salesperson().init_arc().cast().unwrap();
+    let handles:Vec<_> = (0..4).map(|_| {
+        let p = p.clone();
+        thread::spawn(move || p.cast::<Salesperson>().unwrap().sales)
+    }).collect();
+    for h in handles {
+        assert_eq!(h.join().unwrap(), 4);
+    }
+    let w = ArcCast::downgrade(&p);
+    thread::spawn(move || assert!(w.upgrade_as::<Employee>().is_some())).join().unwrap();
+}
+
+#[test]
+fn locked_casting_across_threads() {
+    let p:SyncCast<Person> = salesperson().init_rwlock().cast().unwrap();
+    let writers:Vec<_> = (0..4).map(|_| {
+        let p = p.clone();
+        thread::spawn(move || {
+            for _ in 0..10 {
+                // both layers change under one lock, so readers always see them agree
+                let mut guard = p.write().unwrap();
+                guard.name.push('!');
+                guard.downcast_mut::<Salesperson>().unwrap().sales += 1;
+            }
+        })
+    }).collect();
+    let reader = {
+        let p = p.clone();
+        thread::spawn(move || {
+            for _ in 0..10 {
+                let guard = p.read().unwrap();
+                let s:&Salesperson = guard.downcast().unwrap();
+                assert_eq!(guard.name.len() as u32, s.sales);
+            }
+        })
+    };
+    for h in writers {
+        h.join().unwrap();
+    }
+    reader.join().unwrap();
+    let s = p.cast::<Salesperson>().unwrap();
+    assert_eq!(s.read().unwrap().sales, 44);
+    assert!(p.cast::<Salesperson>().is_some());
+}
+
+#[test]
+fn locked_downcasts_without_locking() {
+    let p:SyncCast<Person> = salesperson().init_rwlock().cast().unwrap();
+    let mut guard = p.write().unwrap();
+    // the handle is cast while this thread holds the write lock
+    let s = p.cast::<Salesperson>().unwrap();
+    assert!(s.cast::<Employee>().is_some());
+    guard.name.push('!');
+    drop(guard);
+    let s = s.read().unwrap();
+    assert_eq!((s.name.as_str(), s.sales), ("John!", 4));
+}
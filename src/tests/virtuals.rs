@@ -0,0 +1,218 @@
+use super::super::*;
+
+inherit!{
+    #[derive(Default)]
+    pub struct Widget {
+        pub width: u32
+    }
+    impl Describe {
+        virtual fn describe(&self) -> String {
+            format!("widget {}", self.width)
+        }
+
+        virtual fn label(&self) -> &str {
+            "none"
+        }
+
+        virtual fn grow(&mut self, by: u32) {
+            self.width += by;
+        }
+    }
+
+    #[derive(Default)]
+    pub struct Button: Widget {
+        pub text: String
+    }
+    impl Describe {
+        override fn describe(&self) -> String {
+            format!("button {} {}", self.width, self.text)
+        }
+
+        override fn label(&self) -> &str {
+            &self.text
+        }
+    }
+
+    #[derive(Default)]
+    pub struct IconButton: Button {
+        pub icon: char,
+        clicks: u32
+    }
+    impl Describe {
+        override fn describe(&self) -> String {
+            format!("{} {}", super_call!(self.describe()), self.icon)
+        }
+
+        override fn grow(&mut self, by: u32) {
+            // calls Widget::grow, as Button doesn't override it
+            super_call!(self.grow(by));
+            super_call!(self.grow(by));
+            self.clicks += 1;
+        }
+    }
+}
+
+fn icon_button() -> Cast<Widget> {
+    construct!( IconButton as Widget {
+        icon: '+',
+        clicks: 0,
+        sup.. Button {
+            text: "add".to_string(),
+            sup.. Widget {
+                width: 10
+            }
+        }
+    })
+}
+
+#[test]
+fn virtual_dispatch() {
+    let mut w = icon_button();
+    // the nearest override of the bottom most sub-type is called, through any layer
+    assert_eq!(w.describe(), "button 10 add +");
+    assert_eq!(w.label(), "add");
+    w.grow(5);
+    assert_eq!(w.width, 20);
+    assert_eq!(w.downcast::<IconButton>().map(|i| i.clicks), Some(1));
+
+    let b: Cast<Button> = w.try_into_sub().ok().unwrap();
+    assert_eq!(b.describe(), "button 20 add +");
+
+    let w = construct!( Widget { width: 3 });
+    assert_eq!(w.describe(), "widget 3");
+    assert_eq!(w.label(), "none");
+}
+
+#[test]
+fn virtual_dispatch_guards() {
+    let cell = construct!( raw IconButton {
+        icon: '-',
+        clicks: 0,
+        sup.. Button {
+            text: "remove".to_string(),
+            sup.. Widget {
+                width: 1
+            }
+        }
+    }).init_cell();
+    assert_eq!(cell.borrow().describe(), "button 1 remove -");
+    cell.borrow_mut().grow(1);
+    assert_eq!(cell.borrow().width, 3);
+}
+
+inherit!{
+    #[derive(Default)]
+    pub abstract struct Node {
+        pub id: u32
+    }
+    impl NodeInfo {
+        virtual fn node_name(&self) -> &'static str;
+
+        virtual fn summary(&self) -> String {
+            format!("{} {}", self.node_name(), self.id)
+        }
+    }
+
+    pub struct Element: Node {
+        pub tag: String
+    }
+    impl NodeInfo {
+        override fn node_name(&self) -> &'static str {
+            "element"
+        }
+    }
+
+    pub struct Text: Node {
+        pub text: String
+    }
+    impl NodeInfo {
+        override fn node_name(&self) -> &'static str {
+            "text"
+        }
+    }
+}
+
+#[test]
+fn abstract_types() {
+    let nodes: Vec<Cast<Node>> = vec![
+        construct!( Element as Node {
+            tag: "p".to_string(),
+            sup.. Node {
+                id: 1
+            }
+        }),
+        // an abstract super-type can still be defaulted
+        construct!( Text as Node {
+            text: "hello".to_string()
+        })
+    ];
+    let summaries: Vec<String> = nodes.iter().map(|n| n.summary()).collect();
+    assert_eq!(summaries, ["element 1", "text 0"]);
+}
+
+#[test]
+#[should_panic(expected = "Cast::new: Node is abstract")]
+fn abstract_objects() {
+    let _: Cast<Node> = Cast::new(Box::new(Node::default()) as Box<dyn UnsafeCastable>);
+}
+
+inherit!{
+    #[derive(Default)]
+    pub struct Comment: Node;
+}
+
+#[test]
+#[should_panic(expected = "abstract virtual method NodeInfo::node_name")]
+fn unimplemented_abstract_virtuals() {
+    // Comment forgot to override node_name, which is caught before it can be called
+    let _ = construct!( Comment { sup.. Node { id: 2 } });
+}
+
+inherit!{
+    #[derive(Default)]
+    pub struct Staff {
+        pub hours: u32
+    }
+    impl Salary {
+        virtual fn pay(&self) -> u32 {
+            self.hours * 10
+        }
+    }
+
+    #[derive(Default)]
+    pub struct Manager: Staff;
+    impl Bonus {
+        virtual fn pay(&self) -> u32 {
+            100
+        }
+    }
+
+    #[derive(Default)]
+    pub struct Director: Manager;
+    impl Salary {
+        override fn pay(&self) -> u32 {
+            // Manager's Bonus::pay isn't the implementation of Salary::pay overridden
+            super_call!(self.pay()) * 2
+        }
+    }
+    impl Bonus {
+        override fn pay(&self) -> u32 {
+            super_call!(self.pay()) + 1
+        }
+    }
+}
+
+impl Staff {
+    // an inherent method of the same name doesn't collide with the implementations
+    pub fn pay(&self) -> u32 {
+        0
+    }
+}
+
+#[test]
+fn virtual_methods_keyed_on_trait() {
+    let d = construct!( Director { sup.. Manager { sup.. Staff { hours: 4 } } });
+    assert_eq!(Salary::pay(&d), 80);
+    assert_eq!(Bonus::pay(&d), 101);
+    assert_eq!(Staff::pay(&d), 0);
+}
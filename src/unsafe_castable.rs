@@ -1,5 +1,7 @@
 use std::any::{Any, TypeId};
-use base::Base;
+use std::fmt;
+use base::{Base, Instance};
+use cast::Cast;
 
 /// A trait-object safe implementation of downcasting using recursion.
 ///
@@ -7,7 +9,7 @@ use base::Base;
 /// parameters, enabling this trait to be coerced into a boxed trait-object. Without this
 /// trait-object, dynamic downcasting would be impossible.
 ///
-/// You should never have to implement or interact with this trait yourself. The `Castable`
+/// You should never have to implement or interact with this trait yourself. The `Handle`
 /// trait provides a nice generic `downcast<T>() -> Option<&T>` method, and the `inherit!` and
 /// `impl_inherit!` macros handle the implementing this trait on your types for you.
 ///
@@ -27,21 +29,30 @@ use base::Base;
 /// assert!(super_type.is_some());
 ///
 /// let super_type:&SuperType = sub_type;
-/// let sub_type = unsafe { super_type.u_downcast(SubType::ident()) };
-/// assert!(sub_type.is_some());
-///
-/// let super_type = &SuperType::default().init();
-/// let sub_type = unsafe { super_type.u_downcast(SubType::ident()) };
+/// let sub_type = unsafe { super_type.u_upcast(SubType::ident()) };
 /// assert!(sub_type.is_none());
 /// # }
 /// ```
-pub trait UnsafeCastable: Any {
-    /// Use a pointer from a `Box` to initialize the `Base` super type.
+///
+/// # Safety
+///
+/// Downcasting trusts every method of this trait to describe the type's real layout: `ident`
+/// and `get_ident` must return the `TypeId` of `Self`, `depth` must be one more than the
+/// super-type's, and `get_super` must return the super-type field. Only the macros should
+/// implement this trait.
+pub unsafe trait UnsafeCastable: Any {
+    /// Use an `Instance` describing the bottom most sub-type to initialize the `Base`
+    /// super type.
     ///
-    /// `*mut UnsafeCastable` points to the bottom most sub-type. It is implemented by
-    /// accessing the super-type field and calling init_base recursively, until the super
-    /// field is `Base`. `Base` then assigns this pointer to its `instance` field.
-    fn init_base(&mut self, s: Option<*mut UnsafeCastable>);
+    /// It is implemented by accessing the super-type field and calling init_base recursively,
+    /// until the super field is `Base`. `Base` then assigns the `Instance` to its `instance`
+    /// field.
+    ///
+    /// # Safety
+    ///
+    /// `s` must describe the object `Self` is part of, at its current location. This is
+    /// called by `Constructable::init` and friends, and should never be called directly.
+    unsafe fn init_base(&mut self, s: Option<Instance>);
 
     /// Returns the `TypeId` of `Self`.
     ///
@@ -49,18 +60,39 @@ pub trait UnsafeCastable: Any {
     /// this method from being implemented on the `UnsafeCastable` trait-object.
     fn ident() -> TypeId where Self: Sized;
 
+    /// Returns the number of super-types between `Self` and `Base`.
+    ///
+    /// `Base` has a depth of `0`, and every sub-type is one deeper than its super-type. A
+    /// type's depth is also its index in the ancestry table recorded in `Base`, which is what
+    /// makes downcasting constant time.
+    fn depth() -> usize where Self: Sized;
+
+    /// Returns the name of `Self`, as written in its declaration.
+    ///
+    /// Like `ident`, this is a type method.
+    fn type_name() -> &'static str where Self: Sized;
+
+    /// Returns the `TypeId` and name of `Self` and each of its super-types, ending with `Base`.
+    ///
+    /// Like `ident`, this is a type method. See `Castable::dynamic_hierarchy` for the
+    /// hierarchy of the object a value is part of.
+    fn hierarchy() -> Vec<(TypeId, &'static str)> where Self: Sized;
+
     /// Returns the `TypeId` of `Self`
     ///
     /// During casting, this value is used to find the correct super-type.
     fn get_ident(&self) -> TypeId;
 
+    /// Returns the name of `Self`, as written in its declaration.
+    fn get_type_name(&self) -> &'static str;
+
     /// Returns the super-type as a trait object.
     ///
     /// This is used to implment recursion during dynamic casting. Since the super-type is
     /// returned as a trait-object, `UnsafeCastable` remains type parameter free, and can
     /// itself be coerced into a trait-object.
-    fn get_super(&self) -> &UnsafeCastable;
-    fn get_super_mut(&mut self) -> &mut UnsafeCastable;
+    fn get_super(&self) -> &dyn UnsafeCastable;
+    fn get_super_mut(&mut self) -> &mut dyn UnsafeCastable;
 
     /// Returns the `Base` type.
     ///
@@ -74,8 +106,62 @@ pub trait UnsafeCastable: Any {
         self.get_super_mut().get_base_mut()
     }
 
-    fn as_any(&self) -> &Any;
-    fn as_any_mut(&mut self) -> &mut Any;
+    /// Returns true if `Self` is an `abstract struct`, which can't be the bottom most sub-type
+    /// of a correctly constructed object.
+    #[doc(hidden)]
+    fn is_abstract(&self) -> bool { false }
+
+    /// Clones `Self` into a new, correctly constructed object.
+    ///
+    /// Returns None if `Self` doesn't implement `Clone`. The macros implement this for you;
+    /// called on the bottom most sub-type, it clones the whole object without slicing it.
+    fn clone_dyn(&self) -> Option<Cast<Base>> { None }
+
+    /// Adds the fields of `Self` and its super-types to `d`, super-types first.
+    ///
+    /// Returns false if any layer left its fields out. Types declared with `inherit!` add their
+    /// fields if they implement `Debug`, e.g. with `#[derive(Debug)]` or `#[inherit(Debug)]`;
+    /// any other layer just calls its super-type.
+    fn debug_fields(&self, d: &mut fmt::DebugStruct) -> bool {
+        self.get_super().debug_fields(d);
+        false
+    }
+
+    /// Formats `Self` as a single struct, with the fields of every layer flattened into it.
+    ///
+    /// Layers that can't be formatted are shown as `..`. `Cast<T>` formats its object with this,
+    /// so the bottom most sub-type is shown whatever `T` is.
+    fn fmt_flat(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut d = f.debug_struct(self.get_type_name());
+        if self.debug_fields(&mut d) {
+            d.finish()
+        } else {
+            d.finish_non_exhaustive()
+        }
+    }
+
+    /// Returns the implementation of the virtual method `name` provided by `Self`.
+    ///
+    /// The implementation is an `unsafe fn(&dyn UnsafeCastable, ..)` or
+    /// `unsafe fn(&mut dyn UnsafeCastable, ..)` pointer, which must be passed the whole object
+    /// `Self` is part of. Only types with `virtual fn` or `override fn` declarations in
+    /// `inherit!` provide any; see the `inherit!` documentation.
+    fn get_virtual(&self, _name: &str) -> Option<&'static dyn Any> { None }
+
+    /// Returns the first abstract virtual method declared by `Self` that nothing in `object`,
+    /// the whole object `Self` is part of, implements.
+    #[doc(hidden)]
+    fn unresolved_virtual(&self, _object: &dyn UnsafeCastable) -> Option<&'static str> { None }
+
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    /// Returns `Self` as an `UnsafeCastable` trait-object.
+    ///
+    /// Lets the owners, which may store the object as any trait-object, hand it out as a plain
+    /// `dyn UnsafeCastable`.
+    fn as_dyn(&self) -> &dyn UnsafeCastable;
+    fn as_dyn_mut(&mut self) -> &mut dyn UnsafeCastable;
 
     /// Dynamically upcasts the type with a matching `TypeId`.
     ///
@@ -87,7 +173,11 @@ pub trait UnsafeCastable: Any {
     /// The type returned is an unsafely transmuted double reference to the actual type
     /// corresponding to the `TypeId`, which gets around using a type parameter, but requires
     /// special handling by the caller.
-    unsafe fn u_upcast(&self, t: TypeId) -> Option<&Any> {
+    ///
+    /// # Safety
+    ///
+    /// `Self` must be correctly constructed, or have never been initialized at all.
+    unsafe fn u_upcast(&self, t: TypeId) -> Option<&dyn Any> {
         if self.get_ident() == t {
             Some(self.as_any())
         } else {
@@ -96,39 +186,15 @@ pub trait UnsafeCastable: Any {
     }
 
     /// Mutable variant of the `u_upcast` method.
-    unsafe fn u_upcast_mut(&mut self, t: TypeId) -> Option<&mut Any> {
-        if self.get_ident() == t {
-            Some(self.as_any_mut())
-        } else {
-            self.get_super_mut().u_upcast_mut(t)
-        }
-    }
-
-    /// Dynamically downcasts the type with a matching `TypeId`.
     ///
-    /// Uses `get_base()` to access `Base.instance`, which is a pointer to the bottom most
-    /// sub-type. Then, it calls `u_upcast()` and returns either the sub-type matching the
-    /// `TypeId`, or `None` if not found. The type hierarchy acts as a single ended linked
-    /// list, with `Base` holding a special reference back to the beginning. Downcasting
-    /// is equivalent to upcasting starting from the bottom most sub-type.
+    /// # Safety
     ///
-    /// The type returned is an unsafely transmuted double reference to the actual type
-    /// corresponding to the `TypeId`, which gets around using a type parameter, but requires
-    /// special handling by the caller.
-    unsafe fn u_downcast(&self, t: TypeId) -> Option<&Any> {
-        if self.get_ident() == t {
-            Some(self.as_any())
-        } else {
-            self.get_base().instance.and_then(|inst| (&*inst).u_upcast(t) )
-        }
-    }
-
-    /// Mutable variant of the `u_downcast` method.
-    unsafe fn u_downcast_mut(&mut self, t: TypeId) -> Option<&mut Any> {
+    /// See `u_upcast`.
+    unsafe fn u_upcast_mut(&mut self, t: TypeId) -> Option<&mut dyn Any> {
         if self.get_ident() == t {
             Some(self.as_any_mut())
         } else {
-            self.get_base_mut().instance.and_then(|inst| (&mut *inst).u_upcast_mut(t) )
+            self.get_super_mut().u_upcast_mut(t)
         }
     }
 }
@@ -0,0 +1,244 @@
+// Lookup used by the methods `inherit!` generates for `virtual fn` declarations.
+use Base;
+use Castable;
+use Layers;
+use UnsafeCastable;
+use {Handle, HandleMut};
+
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+/// Returns the first implementation of the virtual method `name` with the signature `F`,
+/// searching from the bottom most sub-type of `object` up to `Base`.
+///
+/// Panics if there is no implementation.
+#[doc(hidden)]
+pub fn find_virtual<F: Copy + 'static>(object: &dyn UnsafeCastable, name: &str) -> F {
+    let mut layer = object;
+    loop {
+        if let Some(f) = layer.get_virtual(name).and_then(|f| f.downcast_ref::<F>()) {
+            return *f;
+        }
+        if layer.get_ident() == Base::ident() {
+            panic!("virtual method {} has no implementation", name);
+        }
+        layer = layer.get_super();
+    }
+}
+
+/// Returns whether any layer of `object` implements the virtual method `name`.
+#[doc(hidden)]
+pub fn has_virtual(object: &dyn UnsafeCastable, name: &str) -> bool {
+    Layers::new(object, Base::ident()).any(|layer| layer.get_virtual(name).is_some())
+}
+
+/// Panics if `object` doesn't implement every abstract virtual method of its super-types.
+pub(crate) fn check_virtuals(object: &dyn UnsafeCastable) {
+    for layer in Layers::new(object, Base::ident()) {
+        if let Some(name) = layer.unresolved_virtual(object) {
+            panic!("{} doesn't implement the abstract virtual method {}",
+                object.get_type_name(), name);
+        }
+    }
+}
+
+/// Hashes the trait and the name of a virtual method, which keys the `SuperLayer`s its
+/// implementations are found through.
+#[doc(hidden)]
+pub const fn virtual_key(tr: &str, m: &str) -> u64 {
+    // FNV-1a over "Trait::method", the key the implementations are registered under
+    let parts = [tr.as_bytes(), b"::", m.as_bytes()];
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    let mut i = 0;
+    while i < parts.len() {
+        let mut j = 0;
+        while j < parts[i].len() {
+            hash = (hash ^ parts[i][j] as u64).wrapping_mul(0x100_0000_01b3);
+            j += 1;
+        }
+        i += 1;
+    }
+    hash
+}
+
+/// The whole object seen from its `T` layer, by `super_call!`.
+///
+/// It derefs to the `SuperLayer` of the super-type of `T`, so autoderef walks up from the
+/// overriding type to the nearest one implementing `VirtualBody` for the method `KEY`.
+#[doc(hidden)]
+#[repr(transparent)]
+pub struct SuperLayer<T: Castable, const KEY: u64> {
+    _layer: PhantomData<T>,
+    object: dyn UnsafeCastable
+}
+
+impl<T: Castable, const KEY: u64> SuperLayer<T, KEY> {
+    /// Views the whole object `object` from its `T` layer.
+    ///
+    /// # Safety
+    ///
+    /// `object` must be the whole object, e.g. from `Handle::object`, not one of its layers.
+    pub unsafe fn of(object: &dyn UnsafeCastable) -> &Self {
+        &*(object as *const dyn UnsafeCastable as *const SuperLayer<T, KEY>)
+    }
+
+    /// Mutable variant of `of`.
+    ///
+    /// # Safety
+    ///
+    /// `object` must be the whole object, e.g. from `HandleMut::object_mut`.
+    pub unsafe fn of_mut(object: &mut dyn UnsafeCastable) -> &mut Self {
+        &mut *(object as *mut dyn UnsafeCastable as *mut SuperLayer<T, KEY>)
+    }
+
+    /// Views the object of the body of a sub-type from the sub-type's super-type.
+    pub fn above<S>(this: &VirtualSelf<S>) -> &Self where S: Castable + Deref<Target = T> {
+        unsafe { Self::of(&this.object) }
+    }
+
+    /// Mutable variant of `above`.
+    pub fn above_mut<S>(this: &mut VirtualSelf<S>) -> &mut Self
+        where S: Castable + Deref<Target = T>
+    {
+        unsafe { Self::of_mut(&mut this.object) }
+    }
+
+    /// The `self` of the body of the `T` layer.
+    pub fn body_self(&self) -> &VirtualSelf<T> {
+        unsafe { VirtualSelf::of(&self.object) }
+    }
+
+    /// Mutable variant of `body_self`.
+    pub fn body_self_mut(&mut self) -> &mut VirtualSelf<T> {
+        unsafe { VirtualSelf::of_mut(&mut self.object) }
+    }
+}
+
+impl<T: Castable + Deref, const KEY: u64> Deref for SuperLayer<T, KEY>
+    where T::Target: Castable + Sized
+{
+    type Target = SuperLayer<T::Target, KEY>;
+    fn deref(&self) -> &Self::Target {
+        unsafe { SuperLayer::of(&self.object) }
+    }
+}
+
+impl<T: Castable + DerefMut, const KEY: u64> DerefMut for SuperLayer<T, KEY>
+    where T::Target: Castable + Sized
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { SuperLayer::of_mut(&mut self.object) }
+    }
+}
+
+/// Implemented by `inherit!` for the `SuperLayer` of a type with a body for a virtual method,
+/// keyed by the trait and name of the method.
+#[doc(hidden)]
+pub trait VirtualBody<T: Castable> {
+    /// `fn(&VirtualSelf<T>, args..) -> R`
+    type Body: Copy;
+    fn virtual_body(&self) -> (Self::Body, &VirtualSelf<T>);
+}
+
+/// Mutable variant of `VirtualBody`, for methods taking `&mut self`.
+#[doc(hidden)]
+pub trait VirtualBodyMut<T: Castable> {
+    /// `fn(&mut VirtualSelf<T>, args..) -> R`
+    type Body: Copy;
+    fn virtual_body(&mut self) -> (Self::Body, &mut VirtualSelf<T>);
+}
+
+/// The `self` of a `virtual fn` or `override fn` body declared in `inherit!`.
+///
+/// It borrows the whole object, and derefs to the `T` layer the body was declared on. Being a
+/// `Handle`, other virtual methods called on it dispatch on the bottom most sub-type, and it
+/// can be downcast. A `&VirtualSelf<T>` coerces to a `&T` where one is expected.
+///
+/// Only the bodies are handed one, as a layer can't be turned back into the whole object:
+///
+/// ```compile_fail
+/// # #[macro_use]
+/// # extern crate castable;
+/// # use castable::{UnsafeCastable, VirtualSelf};
+/// # inherit! {
+/// #     #[derive(Default)] struct SuperType;
+/// # }
+/// # fn main() {
+/// let layer = SuperType::default();
+/// let this = VirtualSelf::<SuperType>::of(layer.as_dyn());
+/// # }
+/// ```
+#[repr(transparent)]
+pub struct VirtualSelf<T: Castable> {
+    _layer: PhantomData<T>,
+    object: dyn UnsafeCastable
+}
+
+impl<T: Castable> VirtualSelf<T> {
+    /// Views the whole object `object` as its `T` layer.
+    ///
+    /// Panics if the object has no `T` layer.
+    ///
+    /// # Safety
+    ///
+    /// `object` must be the whole object, not one of its layers, as downcasting the
+    /// `VirtualSelf` reaches the rest of it.
+    pub(crate) unsafe fn of(object: &dyn UnsafeCastable) -> &Self {
+        assert!(has_layer::<T>(object), "virtual method called on another layer");
+        &*(object as *const dyn UnsafeCastable as *const VirtualSelf<T>)
+    }
+
+    /// Mutable variant of `of`.
+    ///
+    /// # Safety
+    ///
+    /// `object` must be the whole object.
+    pub(crate) unsafe fn of_mut(object: &mut dyn UnsafeCastable) -> &mut Self {
+        assert!(has_layer::<T>(object), "virtual method called on another layer");
+        &mut *(object as *mut dyn UnsafeCastable as *mut VirtualSelf<T>)
+    }
+}
+
+impl<T: Castable> Deref for VirtualSelf<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        let object = &self.object as *const dyn UnsafeCastable as *mut dyn UnsafeCastable;
+        if let Some(ptr) = unsafe { Base::layer_of::<T, _>(object) } {
+            return unsafe { &*ptr };
+        }
+        // the object isn't correctly constructed, so its layers are walked instead
+        Layers::new(&self.object, T::ident())
+            .find_map(|layer| layer.as_any().downcast_ref())
+            .expect("checked by VirtualSelf::of")
+    }
+}
+
+impl<T: Castable> DerefMut for VirtualSelf<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        let object = &mut self.object as *mut dyn UnsafeCastable;
+        if let Some(ptr) = unsafe { Base::layer_of::<T, _>(object) } {
+            return unsafe { &mut *ptr };
+        }
+        let mut layer = &mut self.object;
+        while layer.get_ident() != T::ident() {
+            layer = layer.get_super_mut();
+        }
+        layer.as_any_mut().downcast_mut().expect("checked by VirtualSelf::of_mut")
+    }
+}
+
+unsafe impl<T: Castable> Handle for VirtualSelf<T> {
+    fn object(&self) -> &dyn UnsafeCastable {
+        &self.object
+    }
+}
+
+unsafe impl<T: Castable> HandleMut for VirtualSelf<T> {
+    fn object_mut(&mut self) -> &mut dyn UnsafeCastable {
+        &mut self.object
+    }
+}
+
+fn has_layer<T: Castable>(object: &dyn UnsafeCastable) -> bool {
+    Layers::new(object, T::ident()).any(|layer| layer.get_ident() == T::ident())
+}
@@ -2,7 +2,6 @@
 //#![feature(trace_macros)]
 //trace_macros!(true);
 
-#[macro_use]
 extern crate castable;
 
 use castable::*;
@@ -54,8 +53,8 @@ fn general() {
     // implicit downcast to Person
     let p:&Person = &s;
     assert!(p.get_ident() == Person::ident());
-    // explicit (up) cast to Employee
-    let e:&Employee = p.downcast().unwrap();
+    // explicit (up) cast to Employee, through the Cast holding the whole object
+    let e:&Employee = s.downcast().unwrap();
     assert!(e.get_ident() == Employee::ident());
 }
 
@@ -83,7 +82,7 @@ fn mutability() {
     {
         let p2:&mut Person = &mut s2;
         p2.name = "Dave".to_string();
-        let e2:&mut Employee = p2.downcast_mut().unwrap();
+        let e2:&mut Employee = s2.downcast_mut().unwrap();
         e2.hours += 3.0;
     }
     assert_eq!(format!("{} {}", s2.name, s2.hours), "Dave 20");